@@ -0,0 +1,192 @@
+use std::error::Error;
+use std::fs;
+use std::path::PathBuf;
+
+use log::{debug, error};
+use toml::Value;
+
+use crate::keys::{Action, KeyChord, KeyMode, Keymap};
+use crate::model::DetailPane;
+use crate::theme::{parse_style, Theme};
+
+const CONFIG_DIR: &str = "rutudu";
+const CONFIG_FILE: &str = "config.toml";
+
+///Everything that can be set in the config file.
+/// Anything not in the file keeps its default
+#[derive(Clone)]
+pub struct Settings {
+    pub keymap: Keymap,
+    pub theme: Theme,
+    ///Numbers in front of the items, eg 3.2:
+    pub show_numbers: bool,
+    ///Where the detail pane starts out
+    pub detail_pane: DetailPane,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            keymap: Keymap::default(),
+            theme: Theme::default(),
+            show_numbers: true,
+            detail_pane: DetailPane::Hidden,
+        }
+    }
+}
+
+///~/.config/rutudu/config.toml, or wherever the platform keeps config
+pub fn default_config_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|d| d.join(CONFIG_DIR).join(CONFIG_FILE))
+}
+
+///Load the settings from the given file, or the default one. A missing file just means defaults,
+/// a broken one gets logged and we carry on with defaults too - better than not starting
+pub fn load_settings(path: Option<&str>) -> Settings {
+    let path = match path.map(PathBuf::from).or_else(default_config_path) {
+        Some(p) => p,
+        None => return Settings::default(),
+    };
+    if !path.exists() {
+        debug!("No config at {}, using defaults", path.display());
+        return Settings::default();
+    }
+    match fs::read_to_string(&path).map_err(|e| e.into()).and_then(|txt| parse_settings(&txt)) {
+        Ok(settings) => settings,
+        Err(why) => {
+            error!("Could not load config from {}: {}", path.display(), why);
+            Settings::default()
+        }
+    }
+}
+
+///Settings out of the text of a config file, eg
+/// ```toml
+/// [keys.insert]
+/// "ctrl+enter" = "confirm"
+/// [keys.edit]
+/// "t" = "toggle_complete"
+/// "x" = "none"
+/// [theme]
+/// name = "light"
+/// mark_urgent = "bold magenta"
+/// [list]
+/// numbers = false
+/// detail = "right"
+/// ```
+pub fn parse_settings(txt: &str) -> Result<Settings, Box<dyn Error>> {
+    let config: Value = txt.parse()?;
+    let mut settings = Settings::default();
+    if let Some(keys) = config.get("keys") {
+        apply_key_bindings(&mut settings.keymap, keys)?;
+    }
+    if let Some(theme) = config.get("theme") {
+        settings.theme = load_theme(theme)?;
+    }
+    if let Some(numbers) = config.get("list").and_then(|list| list.get("numbers")) {
+        settings.show_numbers = numbers.as_bool().ok_or("[list] numbers should be true or false")?;
+    }
+    if let Some(detail) = config.get("list").and_then(|list| list.get("detail")) {
+        settings.detail_pane = detail.as_str()
+                                     .and_then(DetailPane::named)
+                                     .ok_or("[list] detail should be \"right\", \"bottom\" or \"hidden\"")?;
+    }
+    Ok(settings)
+}
+
+///Each table under [keys] is a mode, each entry in it a chord and the action it does.
+/// "none" unbinds the chord
+fn apply_key_bindings(keymap: &mut Keymap, keys: &Value) -> Result<(), Box<dyn Error>> {
+    let modes = keys.as_table().ok_or("[keys] should be a table of modes")?;
+    for (mode_name, bindings) in modes {
+        let mode: KeyMode = mode_name.parse()?;
+        let bindings = bindings.as_table()
+                               .ok_or_else(|| format!("[keys.{}] should be a table of key = action", mode_name))?;
+        for (chord, action) in bindings {
+            let chord: KeyChord = chord.parse()?;
+            let action = action.as_str()
+                               .ok_or_else(|| format!("The action for {} should be a string", chord))?;
+            let action = match action {
+                "none" => None,
+                name => Some(name.parse::<Action>()?),
+            };
+            keymap.bind(mode, chord, action);
+        }
+    }
+    Ok(())
+}
+
+///A built in theme by name, with any of its styles swapped for others
+fn load_theme(theme: &Value) -> Result<Theme, Box<dyn Error>> {
+    let table = theme.as_table().ok_or("[theme] should be a table")?;
+    let mut loaded = match table.get("name") {
+        Some(name) => {
+            let name = name.as_str().ok_or("The theme name should be a string")?;
+            Theme::named(name).ok_or_else(|| format!("No such theme: {}", name))?
+        }
+        None => Theme::default(),
+    };
+    for (name, style) in table.iter().filter(|(name, _)| *name != "name") {
+        let style = style.as_str()
+                         .ok_or_else(|| format!("The style for {} should be a string", name))?;
+        loaded.set_style(name, parse_style(style)?)?;
+    }
+    Ok(loaded)
+}
+
+#[cfg(test)]
+mod tests {
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    use super::*;
+
+    #[test]
+    pub fn test_config_rebinds_keys() {
+        let settings = parse_settings(r#"
+            [keys.insert]
+            "ctrl+enter" = "confirm"
+            "ctrl+s" = "confirm"
+            [keys.edit]
+            "t" = "toggle_complete"
+            "x" = "none"
+        "#).unwrap();
+        let keymap = settings.keymap;
+        let ctrl_s = KeyEvent::new(KeyCode::Char('s'), KeyModifiers::CONTROL);
+        assert_eq!(Some(Action::Confirm), keymap.action_for(KeyMode::Insert, ctrl_s));
+        let t = KeyEvent::new(KeyCode::Char('t'), KeyModifiers::NONE);
+        assert_eq!(Some(Action::ToggleComplete), keymap.action_for(KeyMode::Edit, t));
+        let x = KeyEvent::new(KeyCode::Char('x'), KeyModifiers::NONE);
+        assert_eq!(None, keymap.action_for(KeyMode::Edit, x));
+        //everything else is left alone
+        let a = KeyEvent::new(KeyCode::Char('a'), KeyModifiers::NONE);
+        assert_eq!(Some(Action::AddSibling), keymap.action_for(KeyMode::Edit, a));
+
+        assert!(parse_settings("[keys.edit]\n\"x\" = \"explode\"").is_err());
+        assert!(parse_settings("[keys.nowhere]\n\"x\" = \"quit\"").is_err());
+    }
+
+    #[test]
+    pub fn test_config_picks_theme() {
+        use tui::style::{Color, Style};
+
+        assert_eq!(Theme::default(), parse_settings("").unwrap().theme);
+        let theme = parse_settings("[theme]\nname = \"light\"\nmark_urgent = \"magenta\"").unwrap().theme;
+        assert_eq!("light", theme.name);
+        assert_eq!(Style::default().fg(Color::Magenta), theme.mark_urgent);
+        assert_eq!(Theme::light().text, theme.text);
+        assert!(parse_settings("[theme]\nname = \"neon\"").is_err());
+        assert!(parse_settings("[theme]\nsparkle = \"red\"").is_err());
+    }
+
+    #[test]
+    pub fn test_config_list_settings() {
+        let settings = parse_settings("").unwrap();
+        assert!(settings.show_numbers);
+        assert_eq!(DetailPane::Hidden, settings.detail_pane);
+        let settings = parse_settings("[list]\nnumbers = false\ndetail = \"bottom\"").unwrap();
+        assert!(!settings.show_numbers);
+        assert_eq!(DetailPane::Bottom, settings.detail_pane);
+        assert!(parse_settings("[list]\nnumbers = \"no\"").is_err());
+        assert!(parse_settings("[list]\ndetail = \"left\"").is_err());
+    }
+}
@@ -0,0 +1,333 @@
+use std::fmt;
+use std::fmt::{Display, Formatter};
+
+use chrono::{Datelike, Days, Duration, Local, Months, NaiveDate, NaiveDateTime, Utc, Weekday};
+use log::warn;
+
+///How we write due dates, in the sqlite file and on screen
+pub const DUE_DATE_FMT: &str = "%Y-%m-%d";
+///How sqlite's strftime writes the create date of an item
+pub const CREATE_DATE_FMT: &str = "%Y-%m-%d %H-%M-%S";
+///Prefix that marks a due date in the text of an item, eg due:tomorrow
+const DUE_PREFIX: &str = "due:";
+///Prefix that marks a recurrence rule in the text of an item, eg every:weekdays
+const EVERY_PREFIX: &str = "every:";
+///Prefix that marks an effort estimate in the text of an item, eg est:1h30m
+const ESTIMATE_PREFIX: &str = "est:";
+///Prefix that marks the priority in the text of an item, eg pri:1
+const PRIORITY_PREFIX: &str = "pri:";
+///Priorities go from 1 (most important) to this
+pub const LOWEST_PRIORITY: u8 = 9;
+///Anything due within this many days counts as "due soon"
+pub const DUE_SOON_DAYS: i64 = 2;
+
+///Today, according to the local clock
+pub fn today() -> NaiveDate {
+    Local::now().date_naive()
+}
+
+///Right now, in UTC like sqlite's 'now'
+pub fn now() -> NaiveDateTime {
+    Utc::now().naive_utc()
+}
+
+///How often an item comes back once it is done
+#[derive(Clone, Debug, PartialEq)]
+pub enum Recurrence {
+    Daily,
+    ///Monday to friday
+    Weekdays,
+    Weekly,
+    Monthly,
+    EveryNDays(u16),
+}
+
+impl Recurrence {
+    ///Understands daily, weekdays, weekly, monthly and N/Nd for every N days
+    pub fn parse(rule: &str) -> Option<Self> {
+        let rule = rule.trim().to_ascii_lowercase();
+        match rule.as_str() {
+            "day" | "daily" => Some(Recurrence::Daily),
+            "weekday" | "weekdays" => Some(Recurrence::Weekdays),
+            "week" | "weekly" => Some(Recurrence::Weekly),
+            "month" | "monthly" => Some(Recurrence::Monthly),
+            _ => {
+                let days = rule.strip_suffix('d').unwrap_or(&rule);
+                match days.parse::<u16>() {
+                    Ok(n) if n > 0 => Some(Recurrence::EveryNDays(n)),
+                    _ => None,
+                }
+            }
+        }
+    }
+
+    ///The first date this comes around again after the date passed in.
+    /// None if that's past the last date we can represent
+    pub fn next_after(&self, date: NaiveDate) -> Option<NaiveDate> {
+        match self {
+            Recurrence::Daily => date.checked_add_days(Days::new(1)),
+            Recurrence::Weekdays => {
+                let mut next = date.checked_add_days(Days::new(1))?;
+                while next.weekday() == Weekday::Sat || next.weekday() == Weekday::Sun {
+                    next = next.checked_add_days(Days::new(1))?;
+                }
+                Some(next)
+            }
+            Recurrence::Weekly => date.checked_add_days(Days::new(7)),
+            //chrono clamps the 31st to the end of shorter months for us
+            Recurrence::Monthly => date.checked_add_months(Months::new(1)),
+            Recurrence::EveryNDays(n) => date.checked_add_days(Days::new(u64::from(*n))),
+        }
+    }
+
+    ///When the next occurrence is due, given the one that's being completed.
+    /// We never schedule into the past, so late items skip ahead to after today.
+    /// None if it would be past the last date we can represent
+    pub fn next_due_date(&self, due_date: Option<NaiveDate>, today: NaiveDate) -> Option<NaiveDate> {
+        let mut next = self.next_after(due_date.unwrap_or(today))?;
+        while next <= today {
+            next = self.next_after(next)?;
+        }
+        Some(next)
+    }
+}
+
+///Written the same way it gets parsed, so we can store it like this too
+impl Display for Recurrence {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Recurrence::Daily => write!(f, "daily"),
+            Recurrence::Weekdays => write!(f, "weekdays"),
+            Recurrence::Weekly => write!(f, "weekly"),
+            Recurrence::Monthly => write!(f, "monthly"),
+            Recurrence::EveryNDays(n) => write!(f, "{}d", n),
+        }
+    }
+}
+
+///Will pull the first `<prefix><value>` token out of the text that the parser understands,
+/// returning the text without it and what it parsed to, if anything.
+/// Tokens we can't make sense of are left in the text
+pub fn extract_token<T, F>(text: &str, prefix: &str, parser: F) -> (String, Option<T>)
+where F: Fn(&str) -> Option<T> {
+    let mut parsed = None;
+    let mut words = Vec::new();
+    for word in text.split(' ') {
+        if parsed.is_none() && word.to_ascii_lowercase().starts_with(prefix) {
+            let (_, value) = word.split_at(prefix.len());
+            if let Some(p) = parser(value) {
+                parsed = Some(p);
+                continue;
+            }
+            warn!("Could not make sense of '{}'", word);
+        }
+        words.push(word);
+    }
+    (words.join(" ").trim().to_string(), parsed)
+}
+
+///Will pull a `due:<when>` token out of the text, returning the text without it
+/// and the date it parsed to, if any.
+pub fn extract_due_date(text: &str, today: NaiveDate) -> (String, Option<NaiveDate>) {
+    extract_token(text, DUE_PREFIX, |when| parse_date(when, today))
+}
+
+///Will pull an `every:<rule>` token out of the text, returning the text without it
+/// and the recurrence rule, if any.
+pub fn extract_recurrence(text: &str) -> (String, Option<Recurrence>) {
+    extract_token(text, EVERY_PREFIX, Recurrence::parse)
+}
+
+///Will pull an `est:<effort>` token out of the text, returning the text without it
+/// and the estimate in minutes, if any.
+pub fn extract_estimate(text: &str) -> (String, Option<u32>) {
+    extract_token(text, ESTIMATE_PREFIX, parse_estimate)
+}
+
+///Will pull a `pri:<1-9>` token out of the text, returning the text without it
+/// and the priority, if any.
+pub fn extract_priority(text: &str) -> (String, Option<u8>) {
+    extract_token(text, PRIORITY_PREFIX, |p| {
+        p.parse::<u8>().ok().filter(|p| (1..=LOWEST_PRIORITY).contains(p))
+    })
+}
+
+///Understands 45m, 2h, 1h30m, 1.5h - and a bare number is minutes
+pub fn parse_estimate(effort: &str) -> Option<u32> {
+    let effort = effort.trim().to_ascii_lowercase();
+    if effort.is_empty() {
+        return None;
+    }
+    if let Ok(minutes) = effort.parse::<u32>() {
+        return Some(minutes);
+    }
+    let (hours, minutes) = match effort.split_once('h') {
+        Some((h, m)) => (h, m),
+        None => ("0", effort.as_str()),
+    };
+    let hours = hours.parse::<f32>().ok()?;
+    let minutes = match minutes.strip_suffix('m') {
+        Some(m) => m.parse::<u32>().ok()?,
+        None if minutes.is_empty() => 0,
+        None => return None,
+    };
+    //anything that doesn't fit in the minutes we count in isn't an estimate
+    let hour_minutes = (hours * 60.0).round();
+    if !(0.0..u32::MAX as f32).contains(&hour_minutes) {
+        return None;
+    }
+    (hour_minutes as u32).checked_add(minutes)
+}
+
+///Minutes written the way they get typed in, eg 1h30m
+pub fn format_estimate(minutes: u32) -> String {
+    match (minutes / 60, minutes % 60) {
+        (0, m) => format!("{}m", m),
+        (h, 0) => format!("{}h", h),
+        (h, m) => format!("{}h{}m", h, m),
+    }
+}
+
+///Understands today, tomorrow, weekday names (mon, tuesday), +N/Nd days, Nw weeks
+/// and plain old YYYY-MM-DD dates
+pub fn parse_date(when: &str, today: NaiveDate) -> Option<NaiveDate> {
+    let when = when.trim().to_ascii_lowercase();
+    match when.as_str() {
+        "" => return None,
+        "today" | "tod" => return Some(today),
+        "tomorrow" | "tom" => return Some(today + Duration::days(1)),
+        _ => {}
+    }
+
+    if let Some(weekday_num) = weekday_number(&when) {
+        //always the NEXT one, so due:mon on a monday is a week away
+        let today_num = i64::from(today.weekday().num_days_from_monday());
+        let mut days_ahead = (weekday_num - today_num + 7) % 7;
+        if days_ahead == 0 {
+            days_ahead = 7;
+        }
+        return Some(today + Duration::days(days_ahead));
+    }
+
+    let number_part = when.trim_start_matches('+');
+    if let Some(weeks) = number_part.strip_suffix('w') {
+        //too far out for a date is no date at all, the token stays in the title
+        return weeks.parse::<i64>().ok()
+                    .and_then(Duration::try_weeks)
+                    .and_then(|w| today.checked_add_signed(w));
+    }
+    let days = number_part.strip_suffix('d').unwrap_or(number_part);
+    if let Ok(d) = days.parse::<i64>() {
+        return Duration::try_days(d).and_then(|d| today.checked_add_signed(d));
+    }
+
+    NaiveDate::parse_from_str(&when, DUE_DATE_FMT).ok()
+}
+
+///Monday is 0, sunday is 6. Takes anything from "mon" to "monday"
+fn weekday_number(name: &str) -> Option<i64> {
+    let days = ["monday", "tuesday", "wednesday", "thursday", "friday", "saturday", "sunday"];
+    if name.len() < 3 {
+        return None;
+    }
+    days.iter()
+        .position(|d| d.starts_with(name))
+        .map(|i| i as i64)
+}
+
+///Write the date the way we show and store it
+pub fn format_date(date: &NaiveDate) -> String {
+    date.format(DUE_DATE_FMT).to_string()
+}
+
+///Read a date the way we stored it
+pub fn read_date(date_str: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(date_str, DUE_DATE_FMT).ok()
+}
+
+///Write a create date the way sqlite does
+pub fn format_timestamp(timestamp: &NaiveDateTime) -> String {
+    timestamp.format(CREATE_DATE_FMT).to_string()
+}
+
+///Read a create date the way sqlite wrote it
+pub fn read_timestamp(timestamp_str: &str) -> Option<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(timestamp_str, CREATE_DATE_FMT).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn a_wednesday() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, 21).unwrap()
+    }
+
+    #[test]
+    pub fn test_extract_due_date_from_title() {
+        let (title, due) = extract_due_date("Pay the rent due:tomorrow please", a_wednesday());
+        assert_eq!("Pay the rent please", title);
+        assert_eq!(NaiveDate::from_ymd_opt(2026, 10, 22), due);
+
+        let (title, due) = extract_due_date("Ship it due:2026-11-01", a_wednesday());
+        assert_eq!("Ship it", title);
+        assert_eq!(NaiveDate::from_ymd_opt(2026, 11, 1), due);
+
+        let (title, due) = extract_due_date("Nothing due:whenever", a_wednesday());
+        assert_eq!("Nothing due:whenever", title);
+        assert_eq!(None, due);
+    }
+
+    #[test]
+    pub fn test_recurrence_next_due_date() {
+        let wed = a_wednesday();
+        let (title, rule) = extract_recurrence("Standup every:weekdays");
+        assert_eq!("Standup", title);
+        let rule = rule.unwrap();
+        //friday's standup comes back on monday
+        let friday = NaiveDate::from_ymd_opt(2026, 10, 23).unwrap();
+        assert_eq!(NaiveDate::from_ymd_opt(2026, 10, 26), rule.next_due_date(Some(friday), wed));
+        //done late, it still doesn't land in the past
+        let last_week = NaiveDate::from_ymd_opt(2026, 10, 14).unwrap();
+        assert_eq!(NaiveDate::from_ymd_opt(2026, 10, 22), rule.next_due_date(Some(last_week), wed));
+
+        let jan_31 = NaiveDate::from_ymd_opt(2027, 1, 31).unwrap();
+        assert_eq!(NaiveDate::from_ymd_opt(2027, 2, 28), Recurrence::Monthly.next_after(jan_31));
+        //nowhere to go from the end of the calendar
+        assert_eq!(None, Recurrence::Weekly.next_due_date(Some(NaiveDate::MAX), wed));
+        assert_eq!(None, Recurrence::Weekdays.next_after(NaiveDate::MAX));
+        assert_eq!(Some(Recurrence::EveryNDays(3)), Recurrence::parse(&Recurrence::EveryNDays(3).to_string()));
+    }
+
+    #[test]
+    pub fn test_estimates() {
+        let (title, est) = extract_estimate("Write the report est:1h30m");
+        assert_eq!("Write the report", title);
+        assert_eq!(Some(90), est);
+        assert_eq!(Some(45), parse_estimate("45m"));
+        assert_eq!(Some(150), parse_estimate("2.5h"));
+        assert_eq!(Some(20), parse_estimate("20"));
+        assert_eq!(None, parse_estimate("soon"));
+        assert_eq!(None, parse_estimate("infh1m"));
+        assert_eq!(None, parse_estimate("nanh"));
+        assert_eq!(None, parse_estimate("1h4294967295m"));
+        assert_eq!(None, parse_estimate("99999999999h"));
+        assert_eq!("2h5m", format_estimate(125));
+        assert_eq!("3h", format_estimate(180));
+    }
+
+    #[test]
+    pub fn test_parse_relative_dates() {
+        let wed = a_wednesday();
+        assert_eq!(Some(wed), parse_date("today", wed));
+        assert_eq!(NaiveDate::from_ymd_opt(2026, 10, 26), parse_date("mon", wed));
+        assert_eq!(NaiveDate::from_ymd_opt(2026, 10, 28), parse_date("wednesday", wed));
+        assert_eq!(NaiveDate::from_ymd_opt(2026, 10, 24), parse_date("+3", wed));
+        assert_eq!(NaiveDate::from_ymd_opt(2026, 11, 4), parse_date("2w", wed));
+        assert_eq!(None, parse_date("99999999999w", wed));
+        assert_eq!(None, parse_date("99999999999d", wed));
+        let (title, due) = extract_due_date("Far off due:99999999999w", wed);
+        assert_eq!("Far off due:99999999999w", title);
+        assert_eq!(None, due);
+    }
+}
@@ -1,4 +1,5 @@
 use std::cmp::max;
+use std::collections::HashMap;
 use std::error::Error;
 use log::{debug, error};
 use num_traits::{ToPrimitive, FromPrimitive};
@@ -6,11 +7,13 @@ use num_traits::{ToPrimitive, FromPrimitive};
 
 
 use crate::model::{RutuduList, Item, CompleteStatus, ExpandStatus};
+use crate::dates;
+use crate::dates::Recurrence;
 use rusqlite::{Connection, params};
 use std::path::Path;
 #[cfg(feature="clockrust")]
 use clockrusting::db::ClockRuster;
-use tui::style::Color;
+use chrono::NaiveDate;
 
 pub fn save_list(list: &RutuduList) -> Result<(), Box<dyn Error>> {
     let fp = &list.file_path();
@@ -23,17 +26,24 @@ pub fn save_list(list: &RutuduList) -> Result<(), Box<dyn Error>> {
     debug!("Connection will be file: {}", fp_suffixed);
     let conn = Connection::open(fp_suffixed)?;
     create_table_if_needed(&conn);
+    add_missing_columns(&conn)?;
     match empty_table(&conn){
        Ok(_) => debug!("Emptied table successfully"),
         Err(e) => error!("Could not empty table? {}", e),
     }
     list.item_tree.iter()
         .for_each(|(_, sub_list )|{
-           sub_list.iter().for_each(|item|{
+           //where it sits in the bucket is its order among its siblings
+           sub_list.iter().enumerate().for_each(|(sibling_order, item)|{
                debug!("Trying to insert '{}' item with '{}' id", item.title, item.id);
-               match conn.execute("INSERT INTO rutudu_list(id, parent_id, title, entry, completeStatus, expandStatus , create_date)
-                                    VALUES(?1, ?2, ?3, ?4, ?5, ?6, strftime('%Y-%m-%d %H-%M-%S','now') )",
-                                  params![&item.id, &item.parent_id, &item.title, &item.entry, &item.complete.to_u8(), &item.expand.to_u8()]){
+               let due_date = item.due_date.as_ref().map(dates::format_date);
+               let recurrence = item.recurrence.as_ref().map(|r| r.to_string());
+               let create_date = item.create_date.as_ref().map(dates::format_timestamp);
+               match conn.execute("INSERT INTO rutudu_list(id, parent_id, title, entry, completeStatus, expandStatus , create_date, due_date, recurrence, estimate, priority, sibling_order, mark)
+                                    VALUES(?1, ?2, ?3, ?4, ?5, ?6, COALESCE(?7, strftime('%Y-%m-%d %H-%M-%S','now')), ?8, ?9, ?10, ?11, ?12, ?13 )",
+                                  params![&item.id, &item.parent_id, &item.title, &item.entry, &item.complete.to_u8(), &item.expand.to_u8(),
+                                          &create_date, &due_date, &recurrence, &item.estimate, &item.priority, &(sibling_order as u32),
+                                          &item.mark.map(|m| m.number())]){
                    Ok(updated) => debug!("Number of rows inserted: {}", updated),
                    Err(why) => error!("Failed to insert row: {}", why),
 
@@ -62,6 +72,31 @@ pub fn create_table_if_needed(conn: &Connection) {
     ", []).unwrap();
 }
 
+///Columns that came after the original table - older lists will not have them
+const ADDED_COLUMNS: [(&str, &str); 6] = [
+    ("due_date", "TEXT"),
+    ("recurrence", "TEXT"),
+    ("estimate", "INTEGER"),
+    ("priority", "SMALLINT"),
+    ("sibling_order", "INTEGER"),
+    ("mark", "SMALLINT"),
+];
+
+///Bring lists saved by older versions up to date by adding any columns they are missing
+pub fn add_missing_columns(conn: &Connection) -> Result<(), Box<dyn Error>> {
+    let mut stmt = conn.prepare("PRAGMA table_info(rutudu_list)")?;
+    let existing: Vec<String> = stmt.query_map([], |row| row.get("name"))?
+                                    .filter_map(|c| c.ok())
+                                    .collect();
+    for (column, column_type) in ADDED_COLUMNS.iter() {
+        if !existing.iter().any(|c| c == column) {
+            debug!("Adding missing column {} to rutudu_list", column);
+            conn.execute(&format!("ALTER TABLE rutudu_list ADD COLUMN {} {}", column, column_type), [])?;
+        }
+    }
+    Ok(())
+}
+
 ///Load new list into our current list - gooodbye old list!
 pub fn load_list(tudu_list: &mut RutuduList, file_name: &str) ->Result<(), Box<dyn Error>>{
     //save old one -- no, there may not be one
@@ -72,6 +107,8 @@ pub fn load_list(tudu_list: &mut RutuduList, file_name: &str) ->Result<(), Box<d
 
     let mut items:Vec<Item> = load_items(file_name)?;
     tudu_list.item_tree.clear();
+    tudu_list.hoisted_id = 0;
+    tudu_list.mark_filter = None;
     //don't need to clear the list
     // tudu_list.items.items.clear();
 
@@ -101,17 +138,25 @@ pub fn load_list(tudu_list: &mut RutuduList, file_name: &str) ->Result<(), Box<d
 }
 
 pub fn import_unfinished_items(tudu_list: &mut RutuduList, file_name: &str)->Result<(), Box<dyn Error>>{
-   let mut items = load_items(file_name)?;
+   let items = load_items(file_name)?;
+    //done (complete or cancelled) items stay behind, so unfinished children of done parents
+    //need to hang off the closest unfinished ancestor instead - or the root
+    let parents: HashMap<u32, (u32, bool)> = items.iter()
+        .map(|i| (i.id, (i.parent_id, i.complete.is_done())))
+        .collect();
     //update all the import items ids, and their children
     //those in the root list stay in the root list, but their ids and childrens buckets must increase
     let next_id_start = tudu_list.get_max_id()+1;
-    items.iter_mut()
-        .for_each(|i| {
-            if i.parent_id > 0 {
-                i.parent_id += next_id_start;
-            };
+    items.into_iter()
+        .filter(|i| !i.complete.is_done())
+        .for_each(|mut i| {
+            let mut parent_id = i.parent_id;
+            while let Some((grand_parent_id, true)) = parents.get(&parent_id) {
+                parent_id = *grand_parent_id;
+            }
+            i.parent_id = if parent_id > 0 { parent_id + next_id_start } else { 0 };
             i.id += next_id_start;
-            tudu_list.insert_item(i);
+            tudu_list.insert_item(&mut i);
         });
 
     tudu_list.dirty_list = true;
@@ -122,10 +167,31 @@ pub fn import_unfinished_items(tudu_list: &mut RutuduList, file_name: &str)->Res
 
 }
 
+///Bring over the recurring items from another list that are due by today.
+/// They come in at the root level, without their children, with fresh ids
+pub fn import_recurring_items(tudu_list: &mut RutuduList, file_name: &str, today: NaiveDate) -> Result<(), Box<dyn Error>> {
+    let items = load_items(file_name)?;
+    let mut next_id = tudu_list.get_max_id() + 1;
+    items.into_iter()
+         .filter(|i| i.is_recurring_and_due(today))
+         .for_each(|mut i| {
+             debug!("Seeding recurring item '{}'", i.title);
+             i.id = next_id;
+             i.parent_id = 0;
+             i.expand = ExpandStatus::Closed;
+             next_id += 1;
+             tudu_list.insert_item(&mut i);
+         });
+    tudu_list.dirty_list = true;
+    Ok(())
+}
+
 pub fn load_items(file_name:&str) -> Result<Vec<Item>, Box<dyn Error>>{
     let conn = Connection::open(Path::new(file_name))?;
+    add_missing_columns(&conn)?;
     let mut stmt = conn
-        .prepare("select id, title, entry, parent_id, completeStatus, expandStatus from rutudu_list order by parent_id")?;
+        .prepare("select id, title, entry, parent_id, completeStatus, expandStatus, due_date, recurrence, estimate, priority, create_date, sibling_order, mark
+                  from rutudu_list order by parent_id, sibling_order, id")?;
 
     //need to do child ids someho        Item { id: 0, title, entry, parent_id: parent.id.clone(), child_ids: Vec::new(), expand: ExpandStatus::Closed, complete: CompleteStatus::Incomplete }
      let items = stmt.query_map([],|row|{
@@ -138,9 +204,21 @@ pub fn load_items(file_name:&str) -> Result<Vec<Item>, Box<dyn Error>>{
             complete: FromPrimitive::from_u8(row.get("completeStatus")?).unwrap_or(CompleteStatus::Incomplete),
             expand: FromPrimitive::from_u8(row.get("expandStatus")?).unwrap_or(ExpandStatus::Closed),
             depth:0,
-            order:0,
+            order: row.get::<_, Option<u16>>("sibling_order")?.unwrap_or(0),
             tracking_time: false,
-            color: Color::White,
+            mark: row.get::<_, Option<u8>>("mark")?.and_then(FromPrimitive::from_u8),
+            due_date: row.get::<_, Option<String>>("due_date")?
+                         .and_then(|d| dates::read_date(&d)),
+            recurrence: row.get::<_, Option<String>>("recurrence")?
+                           .and_then(|r| Recurrence::parse(&r)),
+            descendants_done: 0,
+            descendants_total: 0,
+            estimate: row.get("estimate")?,
+            remaining_estimate: 0,
+            priority: row.get("priority")?,
+            create_date: row.get::<_, Option<String>>("create_date")?
+                            .and_then(|d| dates::read_timestamp(&d)),
+            guide: String::new(),
         })
     })?
         .map(|i| i.unwrap()).collect();
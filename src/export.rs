@@ -2,6 +2,7 @@ use std::collections::HashMap;
 use std::error::Error;
 use std::fs;
 use log::debug;
+use crate::dates;
 use crate::model::Item;
 
 pub fn write_list_as_markdown(list_name: &str, list: &HashMap<u32, Vec<Item>>, file_path:&str)->Result<(), Box<dyn Error>>{
@@ -26,8 +27,21 @@ pub fn list_into_string(list: &HashMap<u32, Vec<Item>>, list_idx: u32, depth: u1
           .enumerate()
           .for_each(|(i, item)| {
               let indent = str::repeat(" ", (depth*4).into());
-              contents.push_str(&format!("{}{}. ",indent, i+1));
-              contents.push_str(&item.to_string());
+              contents.push_str(&format!("{}{}. {} {}",indent, i+1, item.complete.markdown_marker(), item.title));
+              if let Some(estimate) = item.estimate {
+                  contents.push_str(&format!(" (est {})", dates::format_estimate(estimate)));
+              }
+              contents.push('\n');
+              //the entry goes under the item, a level further in, a line at a time
+              if !item.entry.trim().is_empty() {
+                  item.entry_lines()
+                      .iter()
+                      .for_each(|line| if line.is_empty() {
+                          contents.push('\n')
+                      } else {
+                          contents.push_str(&format!("{}    {}\n", indent, line))
+                      });
+              }
               if list.contains_key(&item.id){
                   let sub_string = list_into_string(list, item.id, depth+1).unwrap();
                   contents.push_str(&sub_string)
@@ -38,4 +52,23 @@ pub fn list_into_string(list: &HashMap<u32, Vec<Item>>, list_idx: u32, depth: u1
     //if an item has children, call this method on the children before continuing
 
     Ok(contents)
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn test_export_multi_line_entries() {
+        let mut list = HashMap::new();
+        let parent = Item::new(1, "Project", "first line\nsecond line");
+        //lists from before kept a newline at the front of the entry
+        let mut child = Item::new(2, "Task", "\nold style");
+        child.parent_id = 1;
+        let other = Item::new(3, "Other", "");
+        list.insert(0, vec![parent, other]);
+        list.insert(1, vec![child]);
+
+        let expected = "1. [ ] Project\n    first line\n    second line\n    1. [ ] Task\n        old style\n2. [ ] Other\n";
+        assert_eq!(expected, list_into_string(&list, 0, 0).unwrap());
+    }
+}
@@ -0,0 +1,795 @@
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::model::{InputMode, Mark, SortKey};
+
+///Groups of input modes that share the same key bindings
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeyMode {
+    Edit,
+    Visual,
+    ///Adding an item, any of the four ways
+    Insert,
+    Save,
+    ///Open, import and send-to-list all pick a file the same way
+    FileDialog,
+    SendToParent,
+    Sort,
+    Mark,
+    MarkFilter,
+    Quit,
+    Help,
+    #[cfg(feature="clockrust")]
+    Report,
+    ///Any key at all goes back to edit mode
+    Success,
+}
+
+impl KeyMode {
+    ///Which bindings apply in this input mode
+    pub fn for_input_mode(mode: &InputMode) -> Self {
+        match mode {
+            InputMode::Edit => KeyMode::Edit,
+            InputMode::Visual => KeyMode::Visual,
+            InputMode::InsertAtRoot
+            | InputMode::InsertChild
+            | InputMode::InsertParent
+            | InputMode::InsertSibling => KeyMode::Insert,
+            InputMode::Save => KeyMode::Save,
+            InputMode::Open | InputMode::Import | InputMode::SendToList => KeyMode::FileDialog,
+            InputMode::SendToParent => KeyMode::SendToParent,
+            InputMode::Sort => KeyMode::Sort,
+            InputMode::Mark => KeyMode::Mark,
+            InputMode::MarkFilter => KeyMode::MarkFilter,
+            InputMode::Quit => KeyMode::Quit,
+            InputMode::Help => KeyMode::Help,
+            #[cfg(feature="clockrust")]
+            InputMode::PrintReport => KeyMode::Report,
+            InputMode::DisplaySuccess => KeyMode::Success,
+        }
+    }
+
+    ///Every mode, in the order the help shows them
+    pub fn all() -> Vec<KeyMode> {
+        vec![KeyMode::Edit,
+             KeyMode::Visual,
+             KeyMode::Insert,
+             KeyMode::Save,
+             KeyMode::FileDialog,
+             KeyMode::SendToParent,
+             KeyMode::Sort,
+             KeyMode::Mark,
+             KeyMode::MarkFilter,
+             KeyMode::Quit,
+             KeyMode::Help,
+             #[cfg(feature="clockrust")]
+             KeyMode::Report,
+        ]
+    }
+
+    ///Heading for the mode in the help
+    pub fn title(&self) -> &'static str {
+        match self {
+            KeyMode::Edit => "Moving around and changing the list",
+            KeyMode::Visual => "Visual mode (several items selected)",
+            KeyMode::Insert => "Adding an item",
+            KeyMode::Save => "Save as",
+            KeyMode::FileDialog => "Picking a list (open, import, send to)",
+            KeyMode::SendToParent => "Picking where the sent item goes",
+            KeyMode::Sort => "Sort by",
+            KeyMode::Mark => "Mark with",
+            KeyMode::MarkFilter => "Show only",
+            KeyMode::Quit => "Quit?",
+            KeyMode::Help => "This help",
+            #[cfg(feature="clockrust")]
+            KeyMode::Report => "Time tracking report",
+            KeyMode::Success => "Success popup",
+        }
+    }
+
+    ///Typing characters puts them in the text box in these modes
+    pub fn takes_text(&self) -> bool {
+        match self {
+            KeyMode::Insert | KeyMode::Save => true,
+            #[cfg(feature="clockrust")]
+            KeyMode::Report => true,
+            _ => false,
+        }
+    }
+}
+
+///What the modes are called in the config file, eg [keys.file_dialog]
+impl FromStr for KeyMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "edit" => Ok(KeyMode::Edit),
+            "visual" => Ok(KeyMode::Visual),
+            "insert" => Ok(KeyMode::Insert),
+            "save" => Ok(KeyMode::Save),
+            "file_dialog" => Ok(KeyMode::FileDialog),
+            "send_to_parent" => Ok(KeyMode::SendToParent),
+            "sort" => Ok(KeyMode::Sort),
+            "mark" => Ok(KeyMode::Mark),
+            "mark_filter" => Ok(KeyMode::MarkFilter),
+            "quit" => Ok(KeyMode::Quit),
+            "help" => Ok(KeyMode::Help),
+            #[cfg(feature="clockrust")]
+            "report" => Ok(KeyMode::Report),
+            _ => Err(format!("No such mode: {}", s)),
+        }
+    }
+}
+
+///Everything a key can do. What it does can depend on the mode, eg Confirm or Down
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Action {
+    Quit,
+    SaveAs,
+    Save,
+    Open,
+    Import,
+    SendToList,
+    Sort,
+    ToggleComplete,
+    ToggleCompleteWithChildren,
+    ExportMarkdown,
+    CycleStatus,
+    CycleStatusBack,
+    MoveUp,
+    MoveDown,
+    MoveIn,
+    MoveOut,
+    Up,
+    Down,
+    Collapse,
+    Expand,
+    CollapseAll,
+    ExpandAll,
+    OpenAll,
+    ExpandSubtree,
+    ExpandToDepth(usize),
+    Hoist,
+    Unhoist,
+    ToggleNumbers,
+    ToggleDetail,
+    DetailDown,
+    DetailUp,
+    Mark,
+    FilterMarks,
+    VisualRange,
+    VisualPick,
+    Yank,
+    Cut,
+    Duplicate,
+    Paste,
+    PasteAsChild,
+    Delete,
+    Erase,
+    AddSibling,
+    AddChild,
+    AddAtRoot,
+    AddParent,
+    Help,
+    ///Handled in main, it needs the terminal
+    EditInEditor,
+    #[cfg(feature="clockrust")]
+    TrackTime,
+    #[cfg(feature="clockrust")]
+    PrintReport,
+    ///Add the item, save the file, open the list...
+    Confirm,
+    ///Back to edit mode without doing anything
+    Cancel,
+    Newline,
+    Backspace,
+    CursorLeft,
+    CursorRight,
+    CursorUp,
+    CursorDown,
+    LineStart,
+    LineEnd,
+    WordLeft,
+    WordRight,
+    ///Delete the character under the cursor
+    DeleteForward,
+    SortBy(SortKey, bool),
+    ///None takes the mark off
+    SetMark(Option<Mark>),
+    ///None shows everything
+    FilterBy(Option<Mark>),
+}
+
+impl Action {
+    ///What the help says it does
+    pub fn description(&self) -> String {
+        let desc = match self {
+            Action::Quit => "Quit",
+            Action::SaveAs => "Save as...",
+            Action::Save => "Save",
+            Action::Open => "Open a list",
+            Action::Import => "Import unfinished items from a list",
+            Action::SendToList => "Send the item and everything under it to another list",
+            Action::Sort => "Sort the children (or siblings) of the item",
+            Action::ToggleComplete => "Cross out / uncross the item",
+            Action::ToggleCompleteWithChildren => "Cross out / uncross the item and its children",
+            Action::ExportMarkdown => "Export the list as markdown",
+            Action::CycleStatus => "Cycle the status: in progress, waiting, blocked, cancelled",
+            Action::CycleStatusBack => "Cycle the status backwards",
+            Action::MoveUp => "Move the item up among its siblings",
+            Action::MoveDown => "Move the item down among its siblings",
+            Action::MoveIn => "Move the item in, under the sibling above it",
+            Action::MoveOut => "Move the item out, next to its parent",
+            Action::Up => "Up",
+            Action::Down => "Down",
+            Action::Collapse => "Collapse the item",
+            Action::Expand => "Expand the item",
+            Action::CollapseAll => "Collapse everything",
+            Action::ExpandAll => "Expand everything",
+            Action::OpenAll => "Expand everything, entries too",
+            Action::ExpandSubtree => "Expand everything under the item",
+            Action::ExpandToDepth(_) => "Show the list that many levels deep",
+            Action::Hoist => "Zoom in: show only what's under the item",
+            Action::Unhoist => "Zoom back out a level",
+            Action::ToggleNumbers => "Show / hide the numbers in front of the items",
+            Action::ToggleDetail => "Detail pane on the right, at the bottom, or hidden",
+            Action::DetailDown => "Scroll the detail pane down",
+            Action::DetailUp => "Scroll the detail pane up",
+            Action::Mark => "Mark / unmark the item - then pick the mark",
+            Action::FilterMarks => "Show only the items with a mark - then pick the mark",
+            Action::VisualRange => "Select a range of items",
+            Action::VisualPick => "Pick / unpick the item",
+            Action::Yank => "Yank the item and everything under it",
+            Action::Cut => "Cut the item and everything under it",
+            Action::Duplicate => "Duplicate the item and everything under it",
+            Action::Paste => "Paste after the item",
+            Action::PasteAsChild => "Paste as the item's last child",
+            Action::Delete => "Delete the item, keeping its children",
+            Action::Erase => "Erase the item and everything under it",
+            Action::AddSibling => "Add an item next to this one",
+            Action::AddChild => "Add an item under this one",
+            Action::AddAtRoot => "Add an item at the top level",
+            Action::AddParent => "Add an item as this one's parent",
+            Action::Help => "Show this help",
+            Action::EditInEditor => "Edit the item in $EDITOR",
+            #[cfg(feature="clockrust")]
+            Action::TrackTime => "Clock in / out of the item",
+            #[cfg(feature="clockrust")]
+            Action::PrintReport => "Write a time tracking report",
+            Action::Confirm => "Ok",
+            Action::Cancel => "Cancel",
+            Action::Newline => "New line",
+            Action::Backspace => "Delete backwards",
+            Action::CursorLeft => "Cursor left",
+            Action::CursorRight => "Cursor right",
+            Action::CursorUp => "Cursor up a line",
+            Action::CursorDown => "Cursor down a line",
+            Action::LineStart => "Start of the line",
+            Action::LineEnd => "End of the line",
+            Action::WordLeft => "Back a word",
+            Action::WordRight => "Forward a word",
+            Action::DeleteForward => "Delete forwards",
+            Action::SortBy(key, false) => return format!("By {:?}", key).to_lowercase(),
+            Action::SortBy(key, true) => return format!("By {:?}, all the way down", key).to_lowercase(),
+            Action::SetMark(Some(mark)) => return format!("{} (again to unmark)", mark.name()),
+            Action::SetMark(None) => "No mark",
+            Action::FilterBy(Some(mark)) => return format!("{} items", mark.name()),
+            Action::FilterBy(None) => "Everything",
+        };
+        desc.to_string()
+    }
+}
+
+///What the actions are called in the config file, eg "add_child" or "sort_by_title_all"
+impl FromStr for Action {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.to_lowercase().replace('-', "_");
+        if let Some(depth) = name.strip_prefix("expand_to_depth_") {
+            return match depth.parse::<usize>() {
+                Ok(d) if d > 0 => Ok(Action::ExpandToDepth(d)),
+                _ => Err(format!("Bad depth in {}", s)),
+            };
+        }
+        if let Some(key) = name.strip_prefix("sort_by_") {
+            let (key, recursive) = match key.strip_suffix("_all") {
+                Some(k) => (k, true),
+                None => (key, false),
+            };
+            return match SortKey::all().iter().find(|k| k.name() == key) {
+                Some(sort_key) => Ok(Action::SortBy(*sort_key, recursive)),
+                None => Err(format!("Can't sort by {}", key)),
+            };
+        }
+        for mark in Mark::all() {
+            if name == format!("mark_{}", mark.name()) {
+                return Ok(Action::SetMark(Some(mark)));
+            }
+            if name == format!("show_{}", mark.name()) {
+                return Ok(Action::FilterBy(Some(mark)));
+            }
+        }
+        let action = match name.as_str() {
+            "quit" => Action::Quit,
+            "save_as" => Action::SaveAs,
+            "save" => Action::Save,
+            "open" => Action::Open,
+            "import" => Action::Import,
+            "send_to_list" => Action::SendToList,
+            "sort" => Action::Sort,
+            "toggle_complete" => Action::ToggleComplete,
+            "toggle_complete_with_children" => Action::ToggleCompleteWithChildren,
+            "export_markdown" => Action::ExportMarkdown,
+            "cycle_status" => Action::CycleStatus,
+            "cycle_status_back" => Action::CycleStatusBack,
+            "move_up" => Action::MoveUp,
+            "move_down" => Action::MoveDown,
+            "move_in" => Action::MoveIn,
+            "move_out" => Action::MoveOut,
+            "up" => Action::Up,
+            "down" => Action::Down,
+            "collapse" => Action::Collapse,
+            "expand" => Action::Expand,
+            "collapse_all" => Action::CollapseAll,
+            "expand_all" => Action::ExpandAll,
+            "open_all" => Action::OpenAll,
+            "expand_subtree" => Action::ExpandSubtree,
+            "hoist" => Action::Hoist,
+            "unhoist" => Action::Unhoist,
+            "toggle_numbers" => Action::ToggleNumbers,
+            "toggle_detail" => Action::ToggleDetail,
+            "detail_down" => Action::DetailDown,
+            "detail_up" => Action::DetailUp,
+            "mark" => Action::Mark,
+            "unmark" => Action::SetMark(None),
+            "filter_marks" => Action::FilterMarks,
+            "show_everything" => Action::FilterBy(None),
+            "visual_range" => Action::VisualRange,
+            "visual_pick" => Action::VisualPick,
+            "yank" => Action::Yank,
+            "cut" => Action::Cut,
+            "duplicate" => Action::Duplicate,
+            "paste" => Action::Paste,
+            "paste_as_child" => Action::PasteAsChild,
+            "delete" => Action::Delete,
+            "erase" => Action::Erase,
+            "add_sibling" => Action::AddSibling,
+            "add_child" => Action::AddChild,
+            "add_at_root" => Action::AddAtRoot,
+            "add_parent" => Action::AddParent,
+            "help" => Action::Help,
+            "edit_in_editor" => Action::EditInEditor,
+            #[cfg(feature="clockrust")]
+            "track_time" => Action::TrackTime,
+            #[cfg(feature="clockrust")]
+            "print_report" => Action::PrintReport,
+            "confirm" => Action::Confirm,
+            "cancel" => Action::Cancel,
+            "newline" => Action::Newline,
+            "backspace" => Action::Backspace,
+            "cursor_left" => Action::CursorLeft,
+            "cursor_right" => Action::CursorRight,
+            "cursor_up" => Action::CursorUp,
+            "cursor_down" => Action::CursorDown,
+            "line_start" => Action::LineStart,
+            "line_end" => Action::LineEnd,
+            "word_left" => Action::WordLeft,
+            "word_right" => Action::WordRight,
+            "delete_forward" => Action::DeleteForward,
+            _ => return Err(format!("No such action: {}", s)),
+        };
+        Ok(action)
+    }
+}
+
+///A key chord - the key and the modifiers held down with it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KeyChord {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyChord {
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        KeyChord::from(KeyEvent::new(code, modifiers))
+    }
+
+    ///Just the key, no modifiers
+    pub fn key(code: KeyCode) -> Self {
+        KeyChord::new(code, KeyModifiers::NONE)
+    }
+
+    pub fn ctrl(c: char) -> Self {
+        KeyChord::new(KeyCode::Char(c), KeyModifiers::CONTROL)
+    }
+
+    pub fn alt(code: KeyCode) -> Self {
+        KeyChord::new(code, KeyModifiers::ALT)
+    }
+
+    pub fn shift(code: KeyCode) -> Self {
+        KeyChord::new(code, KeyModifiers::SHIFT)
+    }
+}
+
+///Shift is already in the character (X is shift+x), and not every terminal
+/// reports it, so we drop it for characters to make things match
+impl From<KeyEvent> for KeyChord {
+    fn from(event: KeyEvent) -> Self {
+        let mut modifiers = event.modifiers;
+        let code = match event.code {
+            KeyCode::Char(c) if modifiers.contains(KeyModifiers::SHIFT) => {
+                modifiers.remove(KeyModifiers::SHIFT);
+                KeyCode::Char(c.to_ascii_uppercase())
+            }
+            code => code,
+        };
+        KeyChord { code, modifiers }
+    }
+}
+
+impl Display for KeyChord {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "Ctrl+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "Alt+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            write!(f, "Shift+")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::Enter => write!(f, "Enter"),
+            KeyCode::Esc => write!(f, "Esc"),
+            KeyCode::Backspace => write!(f, "Backspace"),
+            KeyCode::Delete => write!(f, "Delete"),
+            KeyCode::Left => write!(f, "Left"),
+            KeyCode::Right => write!(f, "Right"),
+            KeyCode::Up => write!(f, "Up"),
+            KeyCode::Down => write!(f, "Down"),
+            KeyCode::Home => write!(f, "Home"),
+            KeyCode::End => write!(f, "End"),
+            KeyCode::PageUp => write!(f, "PageUp"),
+            KeyCode::PageDown => write!(f, "PageDown"),
+            KeyCode::Tab => write!(f, "Tab"),
+            KeyCode::F(n) => write!(f, "F{}", n),
+            other => write!(f, "{:?}", other),
+        }
+    }
+}
+
+///Chords are written the way the help shows them, eg "ctrl+enter", "alt+a", "X", "space", "f5"
+impl FromStr for KeyChord {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        //a lone "+", or one after the separator, is the plus key - any other trailing "+" is missing its key
+        let (mods, key) = if s == "+" {
+            ("", "+")
+        } else if let Some(mods) = s.strip_suffix("++") {
+            (mods, "+")
+        } else if s.ends_with('+') {
+            return Err(format!("No key after the + in {}", s));
+        } else {
+            match s.rfind('+') {
+                Some(i) => (&s[..i], &s[i + 1..]),
+                None => ("", s),
+            }
+        };
+        let mut modifiers = KeyModifiers::NONE;
+        for m in mods.split('+').filter(|m| !m.is_empty()) {
+            match m.to_lowercase().as_str() {
+                "ctrl" | "control" => modifiers.insert(KeyModifiers::CONTROL),
+                "alt" => modifiers.insert(KeyModifiers::ALT),
+                "shift" => modifiers.insert(KeyModifiers::SHIFT),
+                _ => return Err(format!("No such modifier: {}", m)),
+            }
+        }
+        let code = match key.to_lowercase().as_str() {
+            "space" => KeyCode::Char(' '),
+            "enter" | "return" => KeyCode::Enter,
+            "esc" | "escape" => KeyCode::Esc,
+            "backspace" => KeyCode::Backspace,
+            "delete" | "del" => KeyCode::Delete,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "pageup" => KeyCode::PageUp,
+            "pagedown" => KeyCode::PageDown,
+            "tab" => KeyCode::Tab,
+            lower => match (key.chars().count(), lower.strip_prefix('f').map(|n| n.parse::<u8>())) {
+                (1, _) => KeyCode::Char(key.chars().next().unwrap_or(' ')),
+                (_, Some(Ok(n))) => KeyCode::F(n),
+                _ => return Err(format!("No such key: {}", key)),
+            },
+        };
+        Ok(KeyChord::new(code, modifiers))
+    }
+}
+
+///In this mode, this chord does that
+#[derive(Clone, Debug)]
+pub struct KeyBinding {
+    pub mode: KeyMode,
+    pub chord: KeyChord,
+    pub action: Action,
+}
+
+///All the key bindings - the key handler and the help both work off this
+#[derive(Clone)]
+pub struct Keymap {
+    pub bindings: Vec<KeyBinding>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Keymap { bindings: default_bindings() }
+    }
+}
+
+impl Keymap {
+    ///What the key does in this mode, if anything
+    pub fn action_for(&self, mode: KeyMode, event: KeyEvent) -> Option<Action> {
+        let chord = KeyChord::from(event);
+        self.bindings
+            .iter()
+            .find(|b| b.mode == mode && b.chord == chord)
+            .map(|b| b.action)
+    }
+
+    ///Make the chord do the action in this mode, instead of whatever it did before.
+    /// No action just unbinds it
+    pub fn bind(&mut self, mode: KeyMode, chord: KeyChord, action: Option<Action>) {
+        self.bindings.retain(|b| !(b.mode == mode && b.chord == chord));
+        if let Some(action) = action {
+            self.bindings.push(KeyBinding { mode, chord, action });
+        }
+    }
+
+    ///All the keys that do the action in this mode, eg "j, Down", empty if nothing does
+    pub fn keys_for(&self, mode: KeyMode, action: Action) -> String {
+        self.bindings
+            .iter()
+            .filter(|b| b.mode == mode && b.action == action)
+            .map(|b| b.chord.to_string())
+            .collect::<Vec<String>>()
+            .join(", ")
+    }
+
+    ///For the help: every action in the mode with all the keys that do it, in binding order
+    pub fn describe_mode(&self, mode: KeyMode) -> Vec<(String, String)> {
+        let mut described: Vec<(String, String)> = Vec::new();
+        self.bindings
+            .iter()
+            .filter(|b| b.mode == mode)
+            .for_each(|b| {
+                let desc = b.action.description();
+                match described.iter_mut().find(|(_, d)| *d == desc) {
+                    Some((keys, _)) => keys.push_str(&format!(", {}", b.chord)),
+                    None => described.push((b.chord.to_string(), desc)),
+                }
+            });
+        described
+    }
+}
+
+///The bindings rutudu has always had
+pub fn default_bindings() -> Vec<KeyBinding> {
+    use KeyCode::{Backspace, Char, Delete, Down, End, Enter, Esc, Home, Left, Right, Tab, Up};
+    let key = KeyChord::key;
+    let mut bindings = Vec::new();
+    let mut bind = |mode: KeyMode, chords: Vec<KeyChord>, action: Action| {
+        chords.into_iter()
+              .for_each(|chord| bindings.push(KeyBinding { mode, chord, action }));
+    };
+
+    let edit = KeyMode::Edit;
+    bind(edit, vec![key(Char('a'))], Action::AddSibling);
+    bind(edit, vec![KeyChord::ctrl('a')], Action::AddChild);
+    bind(edit, vec![key(Char('A'))], Action::AddAtRoot);
+    bind(edit, vec![KeyChord::alt(Char('a'))], Action::AddParent);
+    bind(edit, vec![key(Char('j')), key(Down)], Action::Down);
+    bind(edit, vec![key(Char('k')), key(Up)], Action::Up);
+    bind(edit, vec![key(Char('h')), key(Left)], Action::Collapse);
+    bind(edit, vec![key(Char('l')), key(Right)], Action::Expand);
+    bind(edit, vec![key(Char('H'))], Action::CollapseAll);
+    bind(edit, vec![key(Char('L'))], Action::ExpandAll);
+    bind(edit, vec![key(Char('+'))], Action::OpenAll);
+    bind(edit, vec![key(Char('*'))], Action::ExpandSubtree);
+    bind(edit, vec![key(Char('#'))], Action::ToggleNumbers);
+    bind(edit, vec![key(Tab)], Action::ToggleDetail);
+    bind(edit, vec![key(Char('J'))], Action::DetailDown);
+    bind(edit, vec![key(Char('K'))], Action::DetailUp);
+    for depth in 1..=9 {
+        let digit = std::char::from_digit(depth as u32, 10).unwrap_or('1');
+        bind(edit, vec![key(Char(digit))], Action::ExpandToDepth(depth));
+    }
+    bind(edit, vec![key(Char('z'))], Action::Hoist);
+    bind(edit, vec![key(Char('Z'))], Action::Unhoist);
+    bind(edit, vec![key(Char('x'))], Action::ToggleComplete);
+    bind(edit, vec![key(Char('X'))], Action::ToggleCompleteWithChildren);
+    bind(edit, vec![key(Char('c'))], Action::CycleStatus);
+    bind(edit, vec![key(Char('C'))], Action::CycleStatusBack);
+    bind(edit, vec![key(Char('u'))], Action::MoveUp);
+    bind(edit, vec![key(Char('d'))], Action::MoveDown);
+    bind(edit, vec![key(Char('>')), key(Char('i'))], Action::MoveIn);
+    bind(edit, vec![key(Char('<'))], Action::MoveOut);
+    bind(edit, vec![key(Char('m'))], Action::Mark);
+    bind(edit, vec![key(Char('f'))], Action::FilterMarks);
+    bind(edit, vec![key(Char('v'))], Action::VisualRange);
+    bind(edit, vec![key(Char(' '))], Action::VisualPick);
+    bind(edit, vec![key(Char('y'))], Action::Yank);
+    bind(edit, vec![key(Char('D'))], Action::Cut);
+    bind(edit, vec![key(Char('Y'))], Action::Duplicate);
+    bind(edit, vec![key(Char('p'))], Action::Paste);
+    bind(edit, vec![key(Char('P'))], Action::PasteAsChild);
+    bind(edit, vec![key(Delete), key(Backspace)], Action::Delete);
+    bind(edit, vec![key(Char('e'))], Action::Erase);
+    bind(edit, vec![key(Char('E'))], Action::EditInEditor);
+    bind(edit, vec![key(Char('O'))], Action::Sort);
+    bind(edit, vec![key(Char('s'))], Action::Save);
+    bind(edit, vec![key(Char('S'))], Action::SaveAs);
+    bind(edit, vec![key(Char('o'))], Action::Open);
+    bind(edit, vec![key(Char('I'))], Action::Import);
+    bind(edit, vec![key(Char('M'))], Action::SendToList);
+    bind(edit, vec![KeyChord::ctrl('x')], Action::ExportMarkdown);
+    #[cfg(feature="clockrust")]
+    bind(edit, vec![KeyChord::ctrl('t')], Action::TrackTime);
+    #[cfg(feature="clockrust")]
+    bind(edit, vec![KeyChord::alt(Char('t'))], Action::PrintReport);
+    bind(edit, vec![key(Char('?'))], Action::Help);
+    bind(edit, vec![key(Char('q'))], Action::Quit);
+
+    //the same things as edit mode, but for everything selected
+    let visual = KeyMode::Visual;
+    bind(visual, vec![key(Char('j')), key(Down)], Action::Down);
+    bind(visual, vec![key(Char('k')), key(Up)], Action::Up);
+    bind(visual, vec![key(Char('v'))], Action::VisualRange);
+    bind(visual, vec![key(Char(' '))], Action::VisualPick);
+    bind(visual, vec![key(Char('x'))], Action::ToggleComplete);
+    bind(visual, vec![key(Char('c'))], Action::CycleStatus);
+    bind(visual, vec![key(Char('C'))], Action::CycleStatusBack);
+    bind(visual, vec![key(Char('m'))], Action::Mark);
+    bind(visual, vec![key(Char('>')), key(Char('i'))], Action::MoveIn);
+    bind(visual, vec![key(Char('<'))], Action::MoveOut);
+    bind(visual, vec![key(Delete), key(Backspace)], Action::Delete);
+    bind(visual, vec![key(Char('e'))], Action::Erase);
+    bind(visual, vec![key(Esc)], Action::Cancel);
+
+    //terminals don't seem to send ctrl+enter, alt+enter does the job
+    let insert = KeyMode::Insert;
+    bind(insert, vec![KeyChord::alt(Enter), KeyChord::new(Enter, KeyModifiers::CONTROL)], Action::Confirm);
+    bind(insert, vec![key(Enter), KeyChord::shift(Enter)], Action::Newline);
+    bind(insert, vec![key(Backspace)], Action::Backspace);
+    bind(insert, vec![key(Delete)], Action::DeleteForward);
+    bind(insert, vec![key(Left)], Action::CursorLeft);
+    bind(insert, vec![key(Right)], Action::CursorRight);
+    bind(insert, vec![key(Up)], Action::CursorUp);
+    bind(insert, vec![key(Down)], Action::CursorDown);
+    bind(insert, vec![key(Home), KeyChord::ctrl('a')], Action::LineStart);
+    bind(insert, vec![key(End), KeyChord::ctrl('e')], Action::LineEnd);
+    bind(insert, vec![KeyChord::new(Left, KeyModifiers::CONTROL), KeyChord::alt(Char('b'))], Action::WordLeft);
+    bind(insert, vec![KeyChord::new(Right, KeyModifiers::CONTROL), KeyChord::alt(Char('f'))], Action::WordRight);
+    bind(insert, vec![key(Esc)], Action::Cancel);
+
+    let save = KeyMode::Save;
+    bind(save, vec![key(Enter), KeyChord::ctrl('n')], Action::Confirm);
+    bind(save, vec![key(Backspace)], Action::Backspace);
+    bind(save, vec![key(Left)], Action::CursorLeft);
+    bind(save, vec![key(Right)], Action::CursorRight);
+    bind(save, vec![key(Esc)], Action::Cancel);
+
+    for mode in [KeyMode::FileDialog, KeyMode::SendToParent] {
+        bind(mode, vec![key(Char('j')), key(Down)], Action::Down);
+        bind(mode, vec![key(Char('k')), key(Up)], Action::Up);
+        bind(mode, vec![key(Char('l')), key(Right), key(Enter)], Action::Confirm);
+        bind(mode, vec![key(Esc)], Action::Cancel);
+    }
+
+    //lowercase sorts one level, uppercase sorts all the way down
+    let sort = KeyMode::Sort;
+    for (c, sort_key) in [('t', SortKey::Title), ('s', SortKey::Status), ('m', SortKey::Mark),
+                          ('c', SortKey::Created), ('p', SortKey::Priority)] {
+        bind(sort, vec![key(Char(c))], Action::SortBy(sort_key, false));
+        bind(sort, vec![key(Char(c.to_ascii_uppercase()))], Action::SortBy(sort_key, true));
+    }
+    bind(sort, vec![key(Esc)], Action::Cancel);
+
+    //a digit picks the mark, m m is the quick way to mark something urgent
+    let mark = KeyMode::Mark;
+    let filter = KeyMode::MarkFilter;
+    for m in Mark::all() {
+        let digit = std::char::from_digit(m.number() as u32, 10).unwrap_or('1');
+        bind(mark, vec![key(Char(digit))], Action::SetMark(Some(m)));
+        bind(filter, vec![key(Char(digit))], Action::FilterBy(Some(m)));
+    }
+    bind(mark, vec![key(Char('m'))], Action::SetMark(Some(Mark::Urgent)));
+    bind(mark, vec![key(Char('0'))], Action::SetMark(None));
+    bind(mark, vec![key(Esc)], Action::Cancel);
+    bind(filter, vec![key(Char('0')), key(Char('f'))], Action::FilterBy(None));
+    bind(filter, vec![key(Esc)], Action::Cancel);
+
+    let quit = KeyMode::Quit;
+    bind(quit, vec![key(Char('y')), key(Enter)], Action::Confirm);
+    bind(quit, vec![key(Char('n')), key(Esc)], Action::Cancel);
+
+    let help = KeyMode::Help;
+    bind(help, vec![key(Char('j')), key(Down)], Action::Down);
+    bind(help, vec![key(Char('k')), key(Up)], Action::Up);
+    bind(help, vec![key(Esc), key(Char('q')), key(Char('?'))], Action::Cancel);
+
+    #[cfg(feature="clockrust")]
+    {
+        let report = KeyMode::Report;
+        bind(report, vec![key(Enter)], Action::Confirm);
+        bind(report, vec![key(Backspace)], Action::Backspace);
+        bind(report, vec![key(Left)], Action::CursorLeft);
+        bind(report, vec![key(Right)], Action::CursorRight);
+        bind(report, vec![key(Esc)], Action::Cancel);
+    }
+
+    bindings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn test_shift_is_folded_into_the_character() {
+        let keymap = Keymap::default();
+        let shift_x = KeyEvent::new(KeyCode::Char('X'), KeyModifiers::SHIFT);
+        assert_eq!(Some(Action::ToggleCompleteWithChildren), keymap.action_for(KeyMode::Edit, shift_x));
+        let ctrl_a = KeyEvent::new(KeyCode::Char('a'), KeyModifiers::CONTROL);
+        assert_eq!(Some(Action::AddChild), keymap.action_for(KeyMode::Edit, ctrl_a));
+        assert_eq!(None, keymap.action_for(KeyMode::Insert, KeyEvent::new(KeyCode::Char('a'), KeyModifiers::NONE)));
+    }
+
+    #[test]
+    pub fn test_parse_chords_and_actions() {
+        assert_eq!(Ok(KeyChord::new(KeyCode::Enter, KeyModifiers::CONTROL)), "ctrl+enter".parse());
+        assert_eq!(Ok(KeyChord::alt(KeyCode::Char('a'))), "Alt+a".parse());
+        assert_eq!(Ok(KeyChord::key(KeyCode::Char('X'))), "shift+x".parse());
+        assert_eq!(Ok(KeyChord::key(KeyCode::Char('+'))), "+".parse());
+        assert_eq!(Ok(KeyChord::ctrl('+')), "ctrl++".parse());
+        assert_eq!(Ok(KeyChord::key(KeyCode::F(5))), "f5".parse());
+        assert!("hyper+x".parse::<KeyChord>().is_err());
+        assert!("a+".parse::<KeyChord>().is_err());
+        assert!("é+".parse::<KeyChord>().is_err());
+
+        assert_eq!(Ok(Action::AddChild), "add_child".parse());
+        assert_eq!(Ok(Action::ExpandToDepth(3)), "expand_to_depth_3".parse());
+        assert_eq!(Ok(Action::SortBy(SortKey::Title, true)), "sort_by_title_all".parse());
+        assert!("fly_to_the_moon".parse::<Action>().is_err());
+    }
+
+    #[test]
+    pub fn test_help_groups_keys_by_action() {
+        let keymap = Keymap::default();
+        let edit_help = keymap.describe_mode(KeyMode::Edit);
+        assert!(edit_help.contains(&("j, Down".to_string(), Action::Down.description())));
+        assert!(edit_help.contains(&("1, 2, 3, 4, 5, 6, 7, 8, 9".to_string(), Action::ExpandToDepth(1).description())));
+        assert!(edit_help.contains(&("e".to_string(), Action::Erase.description())));
+    }
+
+    #[test]
+    pub fn test_keys_for_follows_rebinding() {
+        let mut keymap = Keymap::default();
+        assert_eq!("j, Down", keymap.keys_for(KeyMode::Edit, Action::Down));
+        keymap.bind(KeyMode::Edit, KeyChord::key(KeyCode::Char('J')), None);
+        assert_eq!("", keymap.keys_for(KeyMode::Edit, Action::DetailDown));
+        keymap.bind(KeyMode::Edit, KeyChord::ctrl('n'), Some(Action::DetailDown));
+        assert_eq!("Ctrl+n", keymap.keys_for(KeyMode::Edit, Action::DetailDown));
+    }
+}
@@ -1,7 +1,9 @@
 use std::error::Error;
-use std::io;
-use std::io::{Stdout, stdout};
-use std::time::{Duration, Instant};
+use std::{env, fs, io, process};
+use std::io::{Stdout, stdout, Write};
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 use chrono::prelude::*;
 use clap::{Command, ArgMatches, Arg};
@@ -10,19 +12,21 @@ use log::{debug, error, LevelFilter};
 //use termion::event::Key;
 //use termion::raw::RawTerminal;
 // use tui::backend::TermionBackend;
-use crossterm::event::{read, Event, KeyCode, KeyEvent, KeyModifiers, EnableMouseCapture, DisableMouseCapture};
+use crossterm::event::{read, Event, KeyCode, MouseButton, MouseEvent, MouseEventKind, EnableMouseCapture, DisableMouseCapture};
 use crossterm::{event, ExecutableCommand, execute, terminal};
 use crossterm::terminal::{ClearType, EnterAlternateScreen, LeaveAlternateScreen, enable_raw_mode, disable_raw_mode};
 use tui::{Frame, backend::CrosstermBackend, Terminal};
 use tui::layout::{Constraint, Direction, Layout, Rect, Alignment};
-use tui::style::{Color, Modifier, Style};
+use tui::style::Modifier;
 use tui::text::{Span, Spans};
-use tui::widgets::{Block, Borders, Clear, List, ListItem, Paragraph, BorderType};
+use tui::widgets::{Block, Borders, Clear, List, ListItem, Paragraph, BorderType, Wrap};
 
 use model::InputMode;
 
 // use crate::events::{Event, Events};
-use crate::model::{ MoveDirection, RutuduList};
+use crate::keys::{Action, Keymap, KeyMode};
+use crate::theme::Theme;
+use crate::model::{DetailPane, ExpandStatus, Mark, MoveDirection, RutuduList, SortKey};
 
 use log4rs::append::console::ConsoleAppender;
 use log4rs::append::file::FileAppender;
@@ -32,12 +36,21 @@ use log4rs::config::{Appender, Root};
 mod model;
 mod db;
 mod export;
+mod dates;
+mod keys;
+mod config;
+mod theme;
 
 use num_traits::cast::ToPrimitive;
 use tui::backend::Backend;
+use unicode_width::UnicodeWidthStr;
 
 // const DATE_FMT: &str = "%Y%m%d%H%M%s";
 const DATE_FMT: &str = "%Y%m%d";
+///How many names to try for the file $EDITOR edits before giving up
+const EDIT_FILE_ATTEMPTS: u32 = 100;
+///Points at the selected item in the list
+const HIGHLIGHT_SYMBOL: &str = ">";
 
 fn init_args() -> ArgMatches {
     Command::new("Rutudu Todo List")
@@ -54,6 +67,14 @@ fn init_args() -> ArgMatches {
             .long("verbose")
             .short('v')
             .help("All the info"))
+        .arg(Arg::new("auto_complete")
+            .long("auto-complete")
+            .help("Complete a parent item once all its children are done"))
+        .arg(Arg::new("config")
+            .short('c')
+            .long("config")
+            .takes_value(true)
+            .help("Config file to use instead of ~/.config/rutudu/config.toml"))
         .arg(Arg::new("time_tracking_file")
             .short('t')
             .long("time-track-file")
@@ -190,90 +211,122 @@ fn main() -> Result<(), Box<dyn Error>> {
     //like in vim, 2 modes, edit and insert
         //few more modes now, we in state machine territory
     // let mut edit_mode = true;
+    //a fresh daily list picks up the recurring items from the last one
+    let is_new_daily_list = args.value_of("list_name").is_none() && !Path::new(list_name).exists();
     let mut tudu_list = RutuduList::default();
+    tudu_list.auto_complete_parents = args.is_present("auto_complete");
     tudu_list.set_file_path( list_name);
     tudu_list.open_list(list_name);
+    if is_new_daily_list {
+        tudu_list.seed_recurring_items("./");
+    }
     debug!("We think our list is unsaved = {}", tudu_list.unsaved.clone());
+    let settings = config::load_settings(args.value_of("config"));
+    let keymap = settings.keymap;
+    tudu_list.theme = settings.theme;
+    tudu_list.show_numbers = settings.show_numbers;
+    tudu_list.detail_pane = settings.detail_pane;
 
     // let mut items = [ListItem::new("Item 1"),
     //     ListItem::new("Item 2"), ListItem::new("Item 3")];
+    //where things got drawn last time, so the mouse knows what it's pointing at
+    let mut list_area = Rect::default();
+    let mut dialog_area = Rect::default();
+    let mut detail_area = Rect::default();
     loop {
         terminal.draw(|f| {
             //get the map and then build a new list and display it
-            let title = tudu_list.list_name();
+            let title = tudu_list.breadcrumb_title();
             // let mut items: Vec<ListItem> = tudu_list.items_as_vec();
             // tudu_list.clear_list();
             tudu_list.rebuild_list_if_dirty();
-            let item_list = tudu_list.items_as_vec();
-            let items = item_list.clone();
-            let mut lst_state = tudu_list.items.state.clone();
-
-            let tui_items = List::new(items)
-                .block(Block::default().title(title).borders(Borders::ALL))
-                // .style(Style::default().fg(Color::White))
-                .highlight_style(Style::default()
-                    .add_modifier(Modifier::BOLD)
-                    .fg(tudu_list.highlight_color()))
-                .highlight_symbol(">");
-
-
             let size = f.size();
-            //split into 3
+            //split into 4, the status bar along the bottom
             let chunks = Layout::default()
                 .direction(Direction::Vertical)
                 .constraints([
                     Constraint::Length(3),
                     Constraint::Min(2),
                     Constraint::Length(3),
+                    Constraint::Length(1),
                 ].as_ref() )
                 .split(size);
+            //the list shares its space with the detail pane, when that's showing
+            let (list_chunk, detail_chunk) = split_for_detail(tudu_list.detail_pane, chunks[1]);
+            //long items wrap inside the border, past the highlight symbol
+            tudu_list.list_width = inside_border(list_chunk).width.saturating_sub(HIGHLIGHT_SYMBOL.width() as u16) as usize;
+            let item_list = tudu_list.items_as_vec();
+            let items = item_list.clone();
+            let mut lst_state = tudu_list.items.state.clone();
 
-            let mnemonics_text = ["Add", "X-out", "Save", "Open", "Quit", ];
+            let tui_items = List::new(items)
+                .block(Block::default().title(title).borders(Borders::ALL))
+                // .style(Style::default().fg(Color::White))
+                .highlight_style(tudu_list.highlight_style())
+                .highlight_symbol(HIGHLIGHT_SYMBOL);
+
+
+
+            let mnemonics_text = ["Add", "X-out", "Save", "Open", "Quit", "?Help", ];
+            let theme = &tudu_list.theme;
             let mnemonics:Vec<Span> = mnemonics_text
                 .iter()
                 .cloned()
                 .flat_map(|t| {
                     let (first, rest) = t.split_at(1);
                         vec![
-                            Span::styled(" [", Style::default().fg(Color::Yellow)),
-                            Span::styled( first, Style::default().fg(Color::Yellow).add_modifier(Modifier::UNDERLINED)),
-                            Span::styled("]", Style::default().fg(Color::Yellow)),
-                            Span::styled(rest, Style::default().fg(Color::LightYellow)),
+                            Span::styled(" [", theme.menu_key),
+                            Span::styled( first, theme.menu_key.add_modifier(Modifier::UNDERLINED)),
+                            Span::styled("]", theme.menu_key),
+                            Span::styled(rest, theme.menu_text),
                         ]
                 }).collect();
+            let mut mnemonics = mnemonics;
+            mnemonics.push(Span::raw("   "));
+            mnemonics.extend(mark_legend(theme));
 
             let menu = Spans::from(mnemonics);
-            let top_text = Paragraph::new(Spans::from(Span::styled("R U T U D U",
-                                                                   Style::default()
-                                                                       .fg(Color::LightCyan)
-                                                                       .add_modifier(Modifier::BOLD))));
+            let top_text = Paragraph::new(Spans::from(Span::styled("R U T U D U", theme.banner)));
 
 
             f.render_widget(Clear, size);
             f.render_widget(top_text, chunks[0]);
             let bottom_text = Paragraph::new(menu)
-                .style(Style::default().fg(Color::Cyan))
+                .style(theme.dialog)
                 .alignment(Alignment::Center)
                 .block(
                     Block::default()
                         .borders(Borders::ALL)
-                        .style(Style::default().fg(Color::LightGreen))
+                        .style(theme.menu_border)
                         .title("[M]nemonics")
                         .border_type(BorderType::Double), );
 
-            f.render_stateful_widget(tui_items, chunks[1], &mut lst_state);
+            f.render_stateful_widget(tui_items, list_chunk, &mut lst_state);
+            list_area = list_chunk;
+            detail_area = detail_chunk.unwrap_or_default();
+            if let Some(area) = detail_chunk {
+                draw_detail_pane(&tudu_list, &keymap, f, area);
+            }
 
             f.render_widget(bottom_text, chunks[2]);
+            draw_status_bar(&tudu_list, f, chunks[3]);
 
             match tudu_list.input_mode {
                 InputMode::InsertAtRoot | InputMode::InsertChild| InputMode::InsertParent | InputMode::InsertSibling =>  show_new_item_input(&mut tudu_list, f),
-                InputMode::Quit => draw_quit_dialog(f),
+                InputMode::Quit => draw_quit_dialog(&tudu_list.theme, f),
                 InputMode::Save => draw_save_dialog(&mut tudu_list,f),
-                InputMode::Open | InputMode::Import =>  draw_open_dialog(&mut tudu_list,f),
-                InputMode::Edit =>  {},
+                InputMode::Open =>  dialog_area = draw_open_dialog(&mut tudu_list, "Open list...", f),
+                InputMode::Import =>  dialog_area = draw_open_dialog(&mut tudu_list, "Import from...", f),
+                InputMode::SendToList =>  dialog_area = draw_open_dialog(&mut tudu_list, "Send to list...", f),
+                InputMode::SendToParent =>  draw_send_parent_dialog(&mut tudu_list, f),
+                InputMode::Sort =>  draw_sort_dialog(&tudu_list.theme, &keymap, f),
+                InputMode::Mark =>  draw_mark_dialog(&tudu_list.theme, "Mark with?", f),
+                InputMode::MarkFilter =>  draw_mark_dialog(&tudu_list.theme, "Show only?", f),
+                InputMode::Help =>  draw_help_dialog(&tudu_list, &keymap, f),
+                InputMode::Edit | InputMode::Visual =>  {},
                 #[cfg(feature ="clockrust")]
                 InputMode::PrintReport => draw_print_report_dialog(&mut tudu_list, f),
-                InputMode::DisplaySuccess => draw_popup("Success!", f, tick_rate),
+                InputMode::DisplaySuccess => draw_popup("Success!", &tudu_list.theme, f, tick_rate),
             }
         })?;//.unwrap();
 
@@ -284,187 +337,46 @@ fn main() -> Result<(), Box<dyn Error>> {
             .unwrap_or_else(||Duration::from_secs(0));
         // if let Event::Input(input) = events.next()? {
         if event::poll(timeout)? {
-            if let Event::Key(input) = read()? {
-                match tudu_list.input_mode {
-                    InputMode::Edit => match input.code {
-                        KeyCode::Char('q') => tudu_list.enter_quit_mode(),
-                        KeyCode::Char('S') => tudu_list.enter_save_mode(),
-                        KeyCode::Char('s') => tudu_list.save(),
-                        KeyCode::Char('o') => tudu_list.enter_open_mode(),
-                        KeyCode::Char('I') => tudu_list.enter_import_mode(),
-
-                        KeyCode::Char('x') => match input.modifiers {
-                            KeyModifiers::NONE => tudu_list.toggle_selected_item_completion_status(),
-                            KeyModifiers::SHIFT => tudu_list.toggle_selected_item_and_children_completion_status(),
-                            KeyModifiers::CONTROL => match tudu_list.export_as_markup() {
-                                Ok(s) => debug!("Successfully exported as markup"),
-                                Err(why) => error!("Failed to export as markup {}", why),
-                            },
-                            _ => debug!("We are in undefined territory"),
-                        }
-                        KeyCode::Char('d') => tudu_list.move_item(MoveDirection::Down),
-                        KeyCode::Char('u') => tudu_list.move_item(MoveDirection::Up),
-                        KeyCode::Char('>') | KeyCode::Char('i') => tudu_list.move_item(MoveDirection::In),
-                        KeyCode::Char('<') => tudu_list.move_item(MoveDirection::Out),
-
-                        KeyCode::Char('h') | KeyCode::Left => tudu_list.collapse_selected(),
-                        KeyCode::Char('j') | KeyCode::Down => tudu_list.down(),
-                        KeyCode::Char('k') | KeyCode::Up => tudu_list.up(),
-                        KeyCode::Char('l') | KeyCode::Right => tudu_list.expand_selected(),
-
-                        KeyCode::Char('m') => tudu_list.mark_selected_item(),
-
-                        KeyCode::Delete | KeyCode::Backspace => tudu_list.delete_selected(),
-                        //ctrl+e ...really? why no ctrl+backspace - guess cos it's a weird hex code not a char...
-                        KeyCode::Char('e') =>  tudu_list.erase_selected(),//does not preserve children
-                        // KeyCode::Ctrl('e') => tudu_list.erase_selected(),//does not preserve children
-
-                        KeyCode::Char('a') => match input.modifiers{
-                            KeyModifiers::NONE => tudu_list.enter_insert_mode(InputMode::InsertSibling),
-                            KeyModifiers::CONTROL => tudu_list.enter_insert_mode(InputMode::InsertChild),
-                            KeyModifiers::SHIFT => tudu_list.enter_insert_mode(InputMode::InsertAtRoot),
-                            KeyModifiers::ALT => tudu_list.enter_insert_mode(InputMode::InsertParent),
-                            _ => debug!("We are in undefined territory"),
-                        },
-
-                        KeyCode::Char('A') => tudu_list.enter_insert_mode(InputMode::InsertAtRoot),
-                        // KeyCode::Char('A') => tudu_list.enter_insert_mode(InputMode::InsertAtRoot),
-                        // KeyCode::Ctrl('a') => tudu_list.enter_insert_mode(InputMode::InsertChild),
-                        // KeyCode::Alt('a') => tudu_list.enter_insert_mode(InputMode::InsertParent),
-
-                        #[cfg(feature = "clockrust")]
-                        KeyCode::Ctrl('t') => if let Some(track_file) = tracking_name {
-                            tudu_list.track_time(Some(track_file));
-                        } else {
-                            let fp = tudu_list.file_path();
-                            let tf = Some(&fp[..]);
-                            tudu_list.track_time(tf);
-                        },
-
-                        #[cfg(feature = "clockrust")]
-                        KeyCode::Alt('t') => tudu_list.enter_print_tracking_report_mode(),
-
-                        _ => {}
-                    },
-                    InputMode::InsertAtRoot
-                    | InputMode::InsertChild
-                    | InputMode::InsertParent
-                    | InputMode::InsertSibling => match input.code {
-                        //what's a better key combo? ctrl+[ does weird things...
-                        //terminal doesn't support ctrl+\n, ctrl/shift don't modify the key being pressed dammit
-                        //alt+\n just does not seem to work?
-                        //Alt+enter now works too hoorah
-                        // KeyCode::Char('n') => match input.modifiers {
-                        //     KeyModifiers::CONTROL => tudu_list.add_input_text_as_item_to_list(),//any way to combine with bottom row? so far not found....
-                        //     _ => debug!("We are in undefined territory"),
-                        // },
-                        // KeyCode::Ctrl('n') => tudu_list.add_input_text_as_item_to_list(),//any way to combine with bottom row? so far not found....
-
-
-                        KeyCode::Enter => match input.modifiers{
-                            KeyModifiers::ALT | KeyModifiers::CONTROL=>{
-                                tudu_list.add_input_text_as_item_to_list();
-                            },
-                            KeyModifiers::NONE | KeyModifiers::SHIFT =>tudu_list.add_character('\n'),
-                            _ => debug!("You are in undefined territory"),
-                        }
-                        // KeyCode::Char(c) => match input.modifiers {
-                        //     KeyModifiers::ALT => if c as u32 == 13 {
-                        //         debug!("Alt was pressed with enter!!");
-                        //         tudu_list.add_input_text_as_item_to_list();
-                        //     } else {
-                        //         debug!("We pressed alt+{}", c);
-                        //         debug!("Ascii val == {}", c as u32);
-                        //     }
-                        //     KeyModifiers::NONE | KeyModifiers::SHIFT =>tudu_list.add_character(c),
-                        //     _ => debug!("You are in undefined territory"),
-                        // }
-                        // KeyCode::Alt(c) => if c as u32 == 13 {
-                        //     debug!("Alt was pressed with enter!!");
-                        //     tudu_list.add_input_text_as_item_to_list();
-                        // } else {
-                        //     debug!("We pressed alt+{}", c);
-                        //     debug!("Ascii val == {}", c as u32);
-                        // }
-                        KeyCode::Backspace => tudu_list.remove_character(),
-                        KeyCode::Left => tudu_list.cursor_left(),
-                        KeyCode::Right => tudu_list.cursor_right(tudu_list.file_path().len()),
-                        KeyCode::Char(c) => tudu_list.add_character(c),//tudu_list.current_item.push(c),
-                        KeyCode::Esc => tudu_list.enter_edit_mode(),
-                        // KeyCode::Char(c) => {println!("{}", c)}
-                        // KeyCode::Ctrl(c) => { println!("{}", c) }
-                        _ => {}
+            let event = read()?;
+            if let Event::Mouse(mouse) = event {
+                handle_mouse(&mut tudu_list, mouse, list_area, detail_area, dialog_area);
+            }
+            if let Event::Key(input) = event {
+                let mode = KeyMode::for_input_mode(&tudu_list.input_mode);
+                match keymap.action_for(mode, input) {
+                    #[cfg(feature = "clockrust")]
+                    Some(Action::TrackTime) => if let Some(track_file) = tracking_name {
+                        tudu_list.track_time(Some(track_file));
+                    } else {
+                        let fp = tudu_list.file_path();
+                        let tf = Some(&fp[..]);
+                        tudu_list.track_time(tf);
                     },
-                    InputMode::Save => match input.code {
-                        KeyCode::Char('n')=> if  input.modifiers == KeyModifiers::CONTROL {
-                                    db::save_list(&tudu_list).unwrap();
-                                    tudu_list.mark_saved();
-                        },
-                        KeyCode::Enter => //if input.modifiers == KeyModifiers::CONTROL{
-                            {
-                                db::save_list(&tudu_list).unwrap();
-                                tudu_list.mark_saved();
-                            }
-                        // }
-                        KeyCode::Char(c) =>  {
-                            debug!("This is the char: {}", c );
-                            tudu_list.add_save_input_char(c);
-                        },
-                        KeyCode::Left => tudu_list.cursor_left(),
-                        KeyCode::Right => tudu_list.cursor_right(tudu_list.file_path().len()),
-                        KeyCode::Backspace => tudu_list.remove_save_file_char(),
-                        KeyCode::Esc => tudu_list.enter_edit_mode(),
-                        _ => {}
-
+                    Some(Action::EditInEditor) => if let Err(why) = edit_in_editor(&mut terminal, &mut tudu_list) {
+                        error!("Failed to edit in editor: {}", why);
+                        tudu_list.set_status_message("Couldn't edit in $EDITOR");
                     },
-                    // InputMode::Open => do_function(&input.code, &mut tudu_list, tudu_list.load_list_from_file_dialog),
-                    InputMode::Open => match input.code {//allow moving up and down to select
-                        KeyCode::Char('j') | KeyCode::Down => tudu_list.open_file_down(),
-                        KeyCode::Char('k') | KeyCode::Up => tudu_list.open_file_up(),
-                        KeyCode::Char('l') | KeyCode::Right | KeyCode::Enter =>
-                            tudu_list.load_list_from_file_dialog(),
-                        KeyCode::Esc => tudu_list.enter_edit_mode(),
-                        _ => {},
+                    Some(action) => if perform_action(&mut tudu_list, mode, action) {
+                        let mut stdout = io::stdout();
+                        stdout.execute(terminal::Clear(ClearType::All))?;
+                        // restore terminal
+                        disable_raw_mode()?;
+                        execute!(
+                            terminal.backend_mut(),
+                            LeaveAlternateScreen,
+                            DisableMouseCapture
+                        )?;
+                        terminal.show_cursor()?;
+                        break;
                     },
-                    InputMode::Import => match input.code {//allow moving up and down to select
-                        KeyCode::Char('j') | KeyCode::Down => tudu_list.open_file_down(),
-                        KeyCode::Char('k') | KeyCode::Up => tudu_list.open_file_up(),
-                        KeyCode::Char('l') | KeyCode::Right | KeyCode::Enter  => tudu_list.import_list_from_file_dialog(),
-                        KeyCode::Esc => tudu_list.enter_edit_mode(),
-                        _ => {},
+                    //whatever you do, go back to edit mode
+                    None if mode == KeyMode::Success => tudu_list.enter_edit_mode(),
+                    //anything else typed goes in the text box
+                    None => if let KeyCode::Char(c) = input.code {
+                        if mode.takes_text() {
+                            type_character(&mut tudu_list, c);
+                        }
                     },
-                    InputMode::Quit => match input.code {
-                        KeyCode::Char('y') | KeyCode::Enter => {
-                            let mut stdout = io::stdout();
-                            stdout.execute(terminal::Clear(ClearType::All))?;
-                            // restore terminal
-                            disable_raw_mode()?;
-                            execute!(
-                                terminal.backend_mut(),
-                                LeaveAlternateScreen,
-                                DisableMouseCapture
-                            )?;
-                            terminal.show_cursor()?;
-                            break;
-                        },
-                        KeyCode::Char('n') | KeyCode::Esc => tudu_list.enter_edit_mode(),
-                        _ => {}
-                    }
-                    #[cfg(feature = "clockrust")]
-                    InputMode::PrintReport => match input.code {
-                        KeyCode::Enter => tudu_list.create_report(),
-                        KeyCode::Char(c) => tudu_list.add_char_to_report_dialog(c),
-                        KeyCode::Left => tudu_list.cursor_left(),
-                        KeyCode::Right => tudu_list.cursor_right(tudu_list.report_path().len()),
-                        KeyCode::Backspace => tudu_list.remove_char_from_report_dialog(),
-                        KeyCode::Esc => tudu_list.enter_edit_mode(),
-                        _ => {},
-                    }
-
-                    InputMode::DisplaySuccess => match input {
-                        //whatever you do, go back to edit mode
-                        _ => tudu_list.enter_edit_mode(),
-                    }
                 }
             };
         }
@@ -473,6 +385,370 @@ fn main() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+///A brand new file only we can read, with the text to edit in it. create_new means we never
+/// follow a link somebody left in the temp dir with a name we might pick - we just try another name
+fn create_edit_file(text: &str) -> io::Result<PathBuf> {
+    for attempt in 0..EDIT_FILE_ATTEMPTS {
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.subsec_nanos());
+        let path = env::temp_dir().join(format!("rutudu-{}-{:x}{:x}.md", process::id(), nanos, attempt));
+        match fs::OpenOptions::new().write(true).create_new(true).mode(0o600).open(&path) {
+            Ok(mut file) => {
+                file.write_all(text.as_bytes())?;
+                return Ok(path);
+            },
+            Err(why) if why.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(why) => return Err(why),
+        }
+    }
+    Err(io::Error::new(io::ErrorKind::AlreadyExists, "Couldn't find a free name for the edit file"))
+}
+
+///Hand the terminal over to $EDITOR (or $VISUAL) to edit the selected item, title on the first line,
+/// entry after it. Everything gets set back up the way main had it once the editor is done
+fn edit_in_editor<B: Backend + io::Write>(terminal: &mut Terminal<B>, tudu_list: &mut RutuduList) -> Result<(), Box<dyn Error>> {
+    let text = match tudu_list.selected_item_as_text() {
+        Some(text) => text,
+        None => return Ok(()),
+    };
+    let path = create_edit_file(&text)?;
+    let editor = env::var("VISUAL")
+        .or_else(|_| env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+    //things like "code --wait" come with their own arguments
+    let mut words = editor.split_whitespace();
+    let program = words.next().unwrap_or("vi");
+
+    disable_raw_mode()?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen, DisableMouseCapture)?;
+    terminal.show_cursor()?;
+    let status = process::Command::new(program).args(words).arg(&path).status();
+    enable_raw_mode()?;
+    execute!(terminal.backend_mut(), EnterAlternateScreen, EnableMouseCapture)?;
+    terminal.clear()?;
+
+    let edited = match status {
+        Ok(status) if status.success() => Some(fs::read_to_string(&path)),
+        Ok(status) => {
+            debug!("{} exited with {}, keeping the item as it was", editor, status);
+            None
+        },
+        Err(why) => {
+            let _ = fs::remove_file(&path);
+            return Err(why.into());
+        },
+    };
+    let _ = fs::remove_file(&path);
+    if let Some(text) = edited {
+        if !tudu_list.update_selected_item_from_text(&text?) {
+            tudu_list.set_status_message("Items need a title, left it alone");
+        }
+    }
+    Ok(())
+}
+
+///Does whatever the key was bound to. Returns true when it's time to quit
+fn perform_action(tudu_list: &mut RutuduList, mode: KeyMode, action: Action) -> bool {
+    match mode {
+        //the same things as edit mode, but for everything selected
+        KeyMode::Visual => match action {
+            Action::Down => tudu_list.down(),
+            Action::Up => tudu_list.up(),
+            Action::VisualRange => tudu_list.toggle_visual_range(),
+            Action::VisualPick => tudu_list.toggle_pick_selected(),
+            Action::ToggleComplete => tudu_list.apply_to_visual_selection(false, |l| l.toggle_selected_item_completion_status()),
+            Action::CycleStatus => tudu_list.apply_to_visual_selection(false, |l| l.cycle_selected_item_status(true)),
+            Action::CycleStatusBack => tudu_list.apply_to_visual_selection(false, |l| l.cycle_selected_item_status(false)),
+            Action::Mark => tudu_list.enter_mark_mode(),
+            Action::MoveIn => tudu_list.apply_to_visual_selection(false, |l| l.move_item(MoveDirection::In)),
+            Action::MoveOut => tudu_list.apply_to_visual_selection(true, |l| l.move_item(MoveDirection::Out)),
+            Action::Delete => tudu_list.apply_to_visual_selection(false, |l| l.delete_selected()),
+            Action::Erase => tudu_list.apply_to_visual_selection(false, |l| l.erase_selected()),
+            Action::Cancel => tudu_list.exit_visual_mode(),
+            _ => {}
+        },
+        KeyMode::Insert => match action {
+            Action::Confirm => tudu_list.add_input_text_as_item_to_list(),
+            Action::Newline => tudu_list.add_character('\n'),
+            Action::Backspace => tudu_list.remove_character(),
+            Action::DeleteForward => tudu_list.delete_character(),
+            Action::CursorLeft => tudu_list.input_left(),
+            Action::CursorRight => tudu_list.input_right(),
+            Action::CursorUp => tudu_list.input_line_up_down(false),
+            Action::CursorDown => tudu_list.input_line_up_down(true),
+            Action::LineStart => tudu_list.input_home(),
+            Action::LineEnd => tudu_list.input_end(),
+            Action::WordLeft => tudu_list.input_word_left(),
+            Action::WordRight => tudu_list.input_word_right(),
+            Action::Cancel => tudu_list.enter_edit_mode(),
+            _ => {}
+        },
+        KeyMode::Save => match action {
+            Action::Confirm => {
+                db::save_list(tudu_list).unwrap();
+                tudu_list.mark_saved();
+            },
+            Action::Backspace => tudu_list.remove_dialog_char(),
+            Action::CursorLeft => tudu_list.cursor_left(),
+            Action::CursorRight => tudu_list.cursor_right(),
+            Action::Cancel => tudu_list.enter_edit_mode(),
+            _ => {}
+        },
+        //allow moving up and down to select
+        KeyMode::FileDialog => match action {
+            Action::Down => tudu_list.open_file_down(),
+            Action::Up => tudu_list.open_file_up(),
+            Action::Confirm => match tudu_list.input_mode {
+                InputMode::Import => tudu_list.import_list_from_file_dialog(),
+                InputMode::SendToList => tudu_list.choose_send_target_from_file_dialog(),
+                _ => tudu_list.load_list_from_file_dialog(),
+            },
+            Action::Cancel => tudu_list.enter_edit_mode(),
+            _ => {}
+        },
+        KeyMode::SendToParent => match action {
+            Action::Down => tudu_list.send_parent_down(),
+            Action::Up => tudu_list.send_parent_up(),
+            Action::Confirm => tudu_list.send_selected_to_target(),
+            Action::Cancel => tudu_list.enter_edit_mode(),
+            _ => {}
+        },
+        KeyMode::Sort => match action {
+            Action::SortBy(key, recursive) => tudu_list.sort_selected(key, recursive),
+            Action::Cancel => tudu_list.enter_edit_mode(),
+            _ => {}
+        },
+        KeyMode::Mark => match action {
+            Action::SetMark(mark) => tudu_list.pick_mark(mark),
+            Action::Cancel => tudu_list.exit_mark_mode(),
+            _ => {}
+        },
+        KeyMode::MarkFilter => match action {
+            Action::FilterBy(mark) => tudu_list.filter_by_mark(mark),
+            Action::Cancel => tudu_list.enter_edit_mode(),
+            _ => {}
+        },
+        KeyMode::Quit => match action {
+            Action::Confirm => return true,
+            Action::Cancel => tudu_list.enter_edit_mode(),
+            _ => {}
+        },
+        KeyMode::Help => match action {
+            Action::Down => tudu_list.help_down(),
+            Action::Up => tudu_list.help_up(),
+            Action::Cancel => tudu_list.enter_edit_mode(),
+            _ => {}
+        },
+        #[cfg(feature = "clockrust")]
+        KeyMode::Report => match action {
+            Action::Confirm => tudu_list.create_report(),
+            Action::Backspace => tudu_list.remove_dialog_char(),
+            Action::CursorLeft => tudu_list.cursor_left(),
+            Action::CursorRight => tudu_list.cursor_right(),
+            Action::Cancel => tudu_list.enter_edit_mode(),
+            _ => {}
+        },
+        KeyMode::Success => tudu_list.enter_edit_mode(),
+        KeyMode::Edit => match action {
+            Action::Quit => tudu_list.enter_quit_mode(),
+            Action::SaveAs => tudu_list.enter_save_mode(),
+            Action::Save => tudu_list.save(),
+            Action::Open => tudu_list.enter_open_mode(),
+            Action::Import => tudu_list.enter_import_mode(),
+            Action::SendToList => tudu_list.enter_send_to_list_mode(),
+            Action::Sort => tudu_list.enter_sort_mode(),
+            Action::Help => tudu_list.enter_help_mode(),
+
+            Action::ToggleComplete => tudu_list.toggle_selected_item_completion_status(),
+            Action::ToggleCompleteWithChildren => tudu_list.toggle_selected_item_and_children_completion_status(),
+            Action::ExportMarkdown => match tudu_list.export_as_markup() {
+                Ok(_) => debug!("Successfully exported as markup"),
+                Err(why) => {
+                    error!("Failed to export as markup {}", why);
+                    tudu_list.set_status_message("Export failed");
+                },
+            },
+            Action::CycleStatus => tudu_list.cycle_selected_item_status(true),
+            Action::CycleStatusBack => tudu_list.cycle_selected_item_status(false),
+            Action::MoveDown => tudu_list.move_item(MoveDirection::Down),
+            Action::MoveUp => tudu_list.move_item(MoveDirection::Up),
+            Action::MoveIn => tudu_list.move_item(MoveDirection::In),
+            Action::MoveOut => tudu_list.move_item(MoveDirection::Out),
+
+            Action::Collapse => tudu_list.collapse_selected(),
+            Action::Down => tudu_list.down(),
+            Action::Up => tudu_list.up(),
+            Action::Expand => tudu_list.expand_selected(),
+            Action::CollapseAll => tudu_list.collapse_all(),
+            Action::ExpandAll => tudu_list.expand_all(ExpandStatus::ShowChildren),
+            Action::OpenAll => tudu_list.expand_all(ExpandStatus::Open),
+            Action::ExpandSubtree => tudu_list.expand_selected_subtree(),
+            Action::ExpandToDepth(levels) => tudu_list.expand_to_depth(levels),
+            Action::Hoist => tudu_list.hoist_selected(),
+            Action::Unhoist => tudu_list.unhoist(),
+            Action::ToggleNumbers => tudu_list.toggle_numbers(),
+            Action::ToggleDetail => tudu_list.toggle_detail_pane(),
+            Action::DetailDown => tudu_list.scroll_detail(true),
+            Action::DetailUp => tudu_list.scroll_detail(false),
+
+            Action::Mark => tudu_list.enter_mark_mode(),
+            Action::FilterMarks => tudu_list.enter_mark_filter_mode(),
+            Action::VisualRange => tudu_list.enter_visual_mode(true),
+            Action::VisualPick => tudu_list.enter_visual_mode(false),
+
+            Action::Yank => tudu_list.yank_selected(),
+            Action::Cut => tudu_list.cut_selected(),
+            Action::Duplicate => tudu_list.duplicate_selected(),
+            Action::Paste => tudu_list.paste(false),
+            Action::PasteAsChild => tudu_list.paste(true),
+
+            Action::Delete => tudu_list.delete_selected(),
+            Action::Erase => tudu_list.erase_selected(),//does not preserve children
+
+            Action::AddSibling => tudu_list.enter_insert_mode(InputMode::InsertSibling),
+            Action::AddChild => tudu_list.enter_insert_mode(InputMode::InsertChild),
+            Action::AddAtRoot => tudu_list.enter_insert_mode(InputMode::InsertAtRoot),
+            Action::AddParent => tudu_list.enter_insert_mode(InputMode::InsertParent),
+
+            #[cfg(feature = "clockrust")]
+            Action::PrintReport => tudu_list.enter_print_tracking_report_mode(),
+            _ => {}
+        },
+    }
+    false
+}
+
+///Inside the border of a block drawn in this area
+fn inside_border(area: Rect) -> Rect {
+    Rect::new(area.x + 1, area.y + 1, area.width.saturating_sub(2), area.height.saturating_sub(2))
+}
+
+fn contains(area: Rect, column: u16, row: u16) -> bool {
+    column >= area.x && column < area.x + area.width && row >= area.y && row < area.y + area.height
+}
+
+///Room for the list, and for the detail pane if it's showing
+fn split_for_detail(pane: DetailPane, area: Rect) -> (Rect, Option<Rect>) {
+    let direction = match pane {
+        DetailPane::Hidden => return (area, None),
+        DetailPane::Right => Direction::Horizontal,
+        DetailPane::Bottom => Direction::Vertical,
+    };
+    let parts = Layout::default()
+        .direction(direction)
+        .constraints([Constraint::Percentage(60), Constraint::Percentage(40)].as_ref())
+        .split(area);
+    (parts[0], Some(parts[1]))
+}
+
+///A dialog title with hints like "j/k to scroll" from whatever keys do those things now.
+/// A hint is left out if any of its actions has no key
+fn key_hint(keymap: &Keymap, mode: KeyMode, title: &str, hints: &[(&[Action], &str)]) -> String {
+    let hints: Vec<String> = hints
+        .iter()
+        .filter_map(|(actions, what)| {
+            let keys: Vec<String> = actions.iter().map(|a| keymap.keys_for(mode, *a)).collect();
+            if keys.iter().any(|k| k.is_empty()) { None } else { Some(format!("{} {}", keys.join("/"), what)) }
+        })
+        .collect();
+    if hints.is_empty() {
+        String::from(title)
+    } else {
+        format!("{} - {}", title, hints.join(", "))
+    }
+}
+
+///Everything about the selected item, scrolled separately from the list
+fn draw_detail_pane<B:Backend>(tudu_list: &RutuduList, keymap: &Keymap, f: &mut Frame<B>, area: Rect) {
+    let title = key_hint(keymap, KeyMode::Edit, "Details", &[(&[Action::DetailDown, Action::DetailUp], "to scroll")]);
+    let detail = Paragraph::new(tudu_list.detail_lines())
+        .wrap(Wrap { trim: false })
+        .scroll((tudu_list.detail_scroll(), 0))
+        .block(Block::default().title(title).borders(Borders::ALL));
+    f.render_widget(detail, area);
+}
+
+///Clicking picks an item (or its expansion symbol), the wheel moves up and down.
+/// In the file dialogs, clicking a file opens it
+fn handle_mouse(tudu_list: &mut RutuduList, mouse: MouseEvent, list_area: Rect, detail_area: Rect, dialog_area: Rect) {
+    let mode = KeyMode::for_input_mode(&tudu_list.input_mode);
+    let scroll_down = match mouse.kind {
+        MouseEventKind::ScrollDown => Some(true),
+        MouseEventKind::ScrollUp => Some(false),
+        _ => None,
+    };
+    let clicked = mouse.kind == MouseEventKind::Down(MouseButton::Left);
+    match mode {
+        KeyMode::Edit | KeyMode::Visual => {
+            match scroll_down {
+                Some(down) if contains(detail_area, mouse.column, mouse.row) => tudu_list.scroll_detail(down),
+                Some(down) => tudu_list.items.scroll(down),
+                None => {}
+            }
+            let inner = inside_border(list_area);
+            let inside = contains(inner, mouse.column, mouse.row);
+            let row = mouse.row.saturating_sub(inner.y) as usize;
+            let column = mouse.column as usize;
+            //past the highlight symbol and the numbering is the [+], then the title
+            let symbol_columns = |list: &RutuduList, idx: usize| {
+                let item = &list.items.items[idx];
+                let start = inner.x as usize + HIGHLIGHT_SYMBOL.width() + item.number_prefix(list.item_number(idx)).width();
+                (start, start + item.expansion_state_symbol().width())
+            };
+            match mouse.kind {
+                MouseEventKind::Down(MouseButton::Left) if inside => {
+                    if let Some(idx) = tudu_list.select_at_row(row, inner.height as usize) {
+                        let (symbol_start, symbol_end) = symbol_columns(tudu_list, idx);
+                        if mode != KeyMode::Edit {
+                            return;
+                        }
+                        if column >= symbol_start && column < symbol_end {
+                            tudu_list.toggle_expansion_selected();
+                        } else {
+                            tudu_list.start_drag();
+                        }
+                    }
+                },
+                MouseEventKind::Drag(MouseButton::Left) if tudu_list.drag.is_some() => {
+                    let idx = if inside {
+                        tudu_list.item_at_row(row, inner.height as usize)
+                    } else {
+                        None
+                    };
+                    let over_title = idx.is_some_and(|idx| column > symbol_columns(tudu_list, idx).1);
+                    tudu_list.drag_over(idx, over_title);
+                },
+                MouseEventKind::Up(MouseButton::Left) => tudu_list.drop_dragged(),
+                _ => {}
+            }
+        },
+        KeyMode::FileDialog => {
+            if let Some(down) = scroll_down {
+                tudu_list.open_file_dialog_files.scroll(down);
+            }
+            let inner = inside_border(dialog_area);
+            if !clicked || !contains(inner, mouse.column, mouse.row) {
+                return;
+            }
+            let files = &mut tudu_list.open_file_dialog_files;
+            if let Some(idx) = files.index_at_row((mouse.row - inner.y) as usize, inner.height as usize, |_, _| 1) {
+                files.state.select(Some(idx));
+                perform_action(tudu_list, mode, Action::Confirm);
+            }
+        },
+        _ => {}
+    }
+}
+
+///A plain character typed into whichever text box is showing
+fn type_character(tudu_list: &mut RutuduList, c: char) {
+    match tudu_list.input_mode {
+        InputMode::Save => tudu_list.add_dialog_char(c),
+        #[cfg(feature = "clockrust")]
+        InputMode::PrintReport => tudu_list.add_dialog_char(c),
+        _ => tudu_list.add_character(c),
+    }
+}
+
 fn do_function<F>(c:&KeyCode, tudu_list:&mut RutuduList, func:F)
 where F:Fn()->(){
     match c{
@@ -485,10 +761,44 @@ where F:Fn()->(){
         }
 }
 
-fn draw_popup<B:Backend>(txt: &str, f: &mut Frame<B>, tick_rate:Duration) {
+///One line along the bottom: mode, where the selected item lives, how much is done,
+/// what's left, whether it's saved, what's being tracked and any passing message
+fn draw_status_bar<B:Backend>(tudu_list: &RutuduList, f: &mut Frame<B>, area: Rect) {
+    let theme = &tudu_list.theme;
+    let separator = || Span::styled(" | ", theme.separator);
+    let mut spans = vec![Span::styled(format!(" {} ", tudu_list.input_mode.name()), theme.status_mode)];
+    if tudu_list.unsaved {
+        spans.push(Span::styled(" [unsaved]", theme.unsaved));
+    }
+    spans.push(Span::raw(" "));
+    spans.push(Span::styled(tudu_list.selected_path().join(" > "), theme.status_text));
+    spans.push(separator());
+    spans.push(Span::raw(format!("{}/{} done", tudu_list.done_count(), tudu_list.size())));
+    if let Some(mark) = tudu_list.mark_filter {
+        spans.push(separator());
+        spans.push(Span::styled(format!("showing {} only", mark.name()), theme.mark_style(mark)));
+    }
+    //how much work is left on the list, if anything has been estimated
+    let remaining = tudu_list.estimate_remaining(0);
+    if remaining > 0 {
+        spans.push(separator());
+        spans.push(Span::raw(format!("{} left", dates::format_estimate(remaining))));
+    }
+    if let Some(tracked) = tudu_list.tracked_item_title() {
+        spans.push(separator());
+        spans.push(Span::styled(format!("tracking: {}", tracked), theme.tracking));
+    }
+    if let Some(msg) = tudu_list.status_message() {
+        spans.push(separator());
+        spans.push(Span::styled(msg.to_string(), theme.message));
+    }
+    f.render_widget(Paragraph::new(Spans::from(spans)), area);
+}
+
+fn draw_popup<B:Backend>(txt: &str, theme: &Theme, f: &mut Frame<B>, tick_rate:Duration) {
     let size = f.size();
     let text = Paragraph::new(txt)
-        .style(Style::default().fg(Color::Cyan))
+        .style(theme.dialog)
         .block(Block::default().borders(Borders::ALL));
     let area = little_popup(20, 3, size);
     f.render_widget(Clear, area);
@@ -496,23 +806,34 @@ fn draw_popup<B:Backend>(txt: &str, f: &mut Frame<B>, tick_rate:Duration) {
     // f.render_widget(quit_text, quit_chunks[0]);
     // terminal.render_widget(text, area);
 }
+///The item being typed in, wrapped to fit, scrolled so the cursor stays in view
 fn show_new_item_input<B:Backend>(tudu_list: &mut RutuduList, f: &mut Frame<B>) {
     let size = f.size();
-    let input_box = Paragraph::new(tudu_list.current_item.as_ref())
-        .style(Style::default().fg(Color::Yellow))
-        .block(Block::default().title("Todo Item").borders(Borders::ALL));
     // let input_box_rect = Rect::new(rect.x + 20, rect.y + 20, 150, 16);
     let area = centered_rect(60, 20, size);
+    let inner = inside_border(area);
+    //the cursor sits after the last character, leave room for it
+    tudu_list.input_width = inner.width.saturating_sub(1).max(1) as usize;
+    let lines: Vec<Spans> = tudu_list.input_lines()
+        .into_iter()
+        .map(|(start, end)| Spans::from(tudu_list.current_item[start..end].to_string()))
+        .collect();
+    let (line, col) = tudu_list.input_cursor_line_col();
+    let scroll = (line + 1).saturating_sub(inner.height as usize) as u16;
+    let input_box = Paragraph::new(lines)
+        .style(tudu_list.theme.dialog_input)
+        .scroll((scroll, 0))
+        .block(Block::default().title("Todo Item").borders(Borders::ALL));
         f.render_widget(Clear, area); //this clears out the background
         f.render_widget(input_box, area);
 
-    f.set_cursor(area.x as u16 + tudu_list.cursor_position[0], area.y as u16 + tudu_list.cursor_position[1]);
+    f.set_cursor(inner.x + col as u16, inner.y + line as u16 - scroll);
 }
 
-fn draw_quit_dialog<B:Backend>(f: &mut Frame<B>) {
+fn draw_quit_dialog<B:Backend>(theme: &Theme, f: &mut Frame<B>) {
     let rect = f.size();
     let button_text = Paragraph::new("[Y][N]")
-        .style(Style::default().fg(Color::Cyan))
+        .style(theme.dialog)
         .block(Block::default().borders(Borders::ALL).title("Really Quit?"));
     // let area = centered_rect(10, 16, size);
     let area = little_popup(20, 3, f.size());
@@ -521,19 +842,88 @@ fn draw_quit_dialog<B:Backend>(f: &mut Frame<B>) {
         f.render_widget(button_text, area);
 }
 
+///Draw dialog listing what we can sort by, with whatever keys do it now
+fn draw_sort_dialog<B:Backend>(theme: &Theme, keymap: &Keymap, f: &mut Frame<B>) {
+    let choices = |recursive: bool| SortKey::all()
+        .iter()
+        .filter_map(|sort_key| {
+            let keys = keymap.keys_for(KeyMode::Sort, Action::SortBy(*sort_key, recursive));
+            if keys.is_empty() { None } else { Some(format!("[{}]{}", keys, sort_key.name())) }
+        })
+        .collect::<Vec<String>>()
+        .join(" ");
+    let sort_text = Paragraph::new(format!("One level:   {}\nAll the way: {}", choices(false), choices(true)))
+        .style(theme.dialog)
+        .block(Block::default().borders(Borders::ALL).title("Sort by?"));
+    let area = little_popup(66, 4, f.size());
+
+        f.render_widget(Clear, area);
+        f.render_widget(sort_text, area);
+}
+
+///Which mark is which - the digit that picks it, in its own colours
+fn mark_legend(theme: &Theme) -> Vec<Span<'static>> {
+    Mark::all()
+        .iter()
+        .map(|m| Span::styled(format!(" [{}]{}", m.number(), m.name()), theme.mark_style(*m)))
+        .collect()
+}
+
+///Draw dialog with the legend of the marks to pick from
+fn draw_mark_dialog<B:Backend>(theme: &Theme, title: &str, f: &mut Frame<B>) {
+    let mut legend = mark_legend(theme);
+    legend.push(Span::styled(" [0]none", theme.dialog));
+    let mark_text = Paragraph::new(Spans::from(legend))
+        .style(theme.dialog)
+        .block(Block::default().borders(Borders::ALL).title(title));
+    let area = little_popup(50, 3, f.size());
+
+        f.render_widget(Clear, area);
+        f.render_widget(mark_text, area);
+}
+
+///Draw the help - every key in every mode, straight from the keymap
+fn draw_help_dialog<B:Backend>(tudu_list: &RutuduList, keymap: &Keymap, f: &mut Frame<B>) {
+    let heading = tudu_list.theme.help_heading;
+    let key_style = tudu_list.theme.help_keys;
+    let lines: Vec<Spans> = KeyMode::all()
+        .into_iter()
+        .flat_map(|mode| {
+            let mut mode_lines = vec![Spans::from(Span::styled(mode.title(), heading))];
+            mode_lines.extend(keymap.describe_mode(mode)
+                .into_iter()
+                .map(|(keys, desc)| Spans::from(vec![
+                    Span::styled(format!("  {:<20}", keys), key_style),
+                    Span::raw(desc),
+                ])));
+            mode_lines.push(Spans::from(""));
+            mode_lines
+        })
+        .collect();
+
+    let help_text = Paragraph::new(lines)
+        .style(tudu_list.theme.dialog)
+        .scroll((tudu_list.help_scroll, 0))
+        .block(Block::default().borders(Borders::ALL).title(key_hint(keymap, KeyMode::Help, "Keys",
+                                                                    &[(&[Action::Down, Action::Up], "to scroll"), (&[Action::Cancel], "to close")])));
+    let area = centered_rect(80, 80, f.size());
+
+    f.render_widget(Clear, area);
+    f.render_widget(help_text, area);
+}
+
 ///Draw dialog that allows saving of the tudulist
 /// Allows changing of the filename
 fn draw_save_dialog<B:Backend>(tudu_list: &mut RutuduList, frame: &mut Frame<B>){
     let rect = frame.size();
     let save_text = Paragraph::new(tudu_list.file_path())
-        .style(Style::default().fg(Color::Cyan))
+        .style(tudu_list.theme.dialog)
         .block(Block::default().borders(Borders::ALL).title("[S]ave?"));
     let area = little_popup(40,5, rect);
 
         frame.render_widget(Clear, area);
         frame.render_widget(save_text, area);
-    // tudu_list.cursor_position[0] =
-    frame.set_cursor(area.x as u16 + tudu_list.cursor_position[0] as u16 +1, area.y as u16 + tudu_list.cursor_position[1] );
+    frame.set_cursor(area.x + 1 + tudu_list.dialog_cursor_column(), area.y + 1);
 }
 
 ///Draw dialog that allows printing of time tracking report.
@@ -543,20 +933,20 @@ fn draw_print_report_dialog(tudu_list: &mut RutuduList, f: &mut Frame<TermionBac
    let rect = f.size();
     let report_path = tudu_list.report_path();
     let report_path_text = Paragraph::new(report_path)
-        .style(Style::default().fg(Color::Cyan))
+        .style(tudu_list.theme.dialog)
         .block(Block::default().borders(Borders::ALL).title("Save report?"));
     let area = little_popup(70, 5, rect);
 
     f.render_widget(Clear, area);
     f.render_widget(report_path_text, area);
 
-    f.set_cursor(area.x as u16 + tudu_list.cursor_position[0] as u16, area.y as u16 + tudu_list.cursor_position[1] );
+    f.set_cursor(area.x + 1 + tudu_list.dialog_cursor_column(), area.y + 1);
 
 
 }
 
 ///Draw dialog with a display of the files in the current directory
-fn draw_open_dialog<B:Backend>(tudu_list: &mut RutuduList, f: &mut Frame<B>) {
+fn draw_open_dialog<B:Backend>(tudu_list: &mut RutuduList, title: &str, f: &mut Frame<B>) -> Rect {
     tudu_list.scan_files_once();
 
     // debug!("Trying to draw open dialog");
@@ -577,17 +967,37 @@ fn draw_open_dialog<B:Backend>(tudu_list: &mut RutuduList, f: &mut Frame<B>) {
                                              }).collect();
     let file_items = List::new(tudu_spans)
         .block(Block::default()
-            .title("Open list...")
+            .title(title)
             .borders(Borders::ALL)
             .border_type(BorderType::Double)
-            .style(Style::default().fg(Color::Cyan)))
-        .style(Style::default()
-            .fg(Color::LightCyan))
-        .highlight_style(Style::default()
-            .add_modifier(Modifier::BOLD).fg(Color::LightBlue))
+            .style(tudu_list.theme.dialog))
+        .style(tudu_list.theme.dialog_item)
+        .highlight_style(tudu_list.theme.dialog_selected)
         .highlight_symbol("o");
 
         f.render_widget(Clear, rect);
         f.render_stateful_widget(file_items, rect, &mut tudu_file_state);
+        rect
+}
+
+///Draw dialog with every item of the list we're sending to, so we can pick the new parent
+fn draw_send_parent_dialog<B:Backend>(tudu_list: &mut RutuduList, f: &mut Frame<B>) {
+    let mut choice_state = tudu_list.send_parent_choices.state.clone();
+    let choice_spans:Vec<ListItem> = tudu_list.send_parent_choices.items
+                                              .iter()
+                                              .map(|(_, title)| ListItem::new(Spans::from(Span::raw(title.clone()))))
+                                              .collect();
+    let rect = centered_rect(50, 50, f.size());
+    let choices = List::new(choice_spans)
+        .block(Block::default()
+            .title("Send under...")
+            .borders(Borders::ALL)
+            .border_type(BorderType::Double)
+            .style(tudu_list.theme.dialog))
+        .style(tudu_list.theme.dialog_item)
+        .highlight_style(tudu_list.theme.dialog_selected)
+        .highlight_symbol(">");
 
+        f.render_widget(Clear, rect);
+        f.render_stateful_widget(choices, rect, &mut choice_state);
 }
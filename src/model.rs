@@ -1,4 +1,5 @@
 use core::fmt;
+use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::error::Error;
 use std::fmt::{Display, Formatter};
@@ -6,29 +7,171 @@ use std::mem;
 use std::ops::Index;
 use std::os::linux::raw::stat;
 use std::path::Path;
+use std::time::{Duration, Instant};
 
 #[cfg(feature="clockrust")]
 use chrono::{Utc};
+use chrono::{NaiveDate, NaiveDateTime};
 use log::{debug, error, warn};
 use num_derive::{FromPrimitive, ToPrimitive};
 use regex::Regex;
-use tui::style::{Color, Modifier, Style};
+use tui::style::{Modifier, Style};
 use tui::text::{Span, Spans};
 use tui::widgets::{ ListItem, ListState};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
 #[cfg(feature ="clockrust")]
 use clockrusting::db::ClockRuster;
 #[cfg(feature ="clockrust")]
 use clockrusting::command::{Command, CommandType};
 
-use crate::{db, export};
+use crate::{dates, db, export};
+use crate::dates::Recurrence;
+use crate::theme::Theme;
 
 #[ cfg(feature="clockrust") ]
 pub const DEFAULT_REPORT_PATH: &str = "_time_report";
 
-#[derive(FromPrimitive, ToPrimitive, Clone)]
+#[derive(FromPrimitive, ToPrimitive, Clone, PartialEq, Debug)]
 pub enum CompleteStatus {
     Incomplete = 1,
     Complete = 2,
+    InProgress = 3,
+    Blocked = 4,
+    ///Waiting on somebody or something else
+    Waiting = 5,
+    ///Not doing it, but not pretending it got done either
+    Cancelled = 6,
+}
+
+impl CompleteStatus {
+    ///Nothing more to do - complete or cancelled
+    pub fn is_done(&self) -> bool {
+        matches!(self, CompleteStatus::Complete | CompleteStatus::Cancelled)
+    }
+
+    ///Next status when cycling with 'c'. Complete is left out, that's what 'x' is for
+    pub fn next(&self) -> Self {
+        match self {
+            CompleteStatus::Incomplete => CompleteStatus::InProgress,
+            CompleteStatus::InProgress => CompleteStatus::Waiting,
+            CompleteStatus::Waiting => CompleteStatus::Blocked,
+            CompleteStatus::Blocked => CompleteStatus::Cancelled,
+            CompleteStatus::Cancelled | CompleteStatus::Complete => CompleteStatus::Incomplete,
+        }
+    }
+
+    ///Previous status when cycling backwards with 'C'
+    pub fn previous(&self) -> Self {
+        match self {
+            CompleteStatus::Incomplete | CompleteStatus::Complete => CompleteStatus::Cancelled,
+            CompleteStatus::InProgress => CompleteStatus::Incomplete,
+            CompleteStatus::Waiting => CompleteStatus::InProgress,
+            CompleteStatus::Blocked => CompleteStatus::Waiting,
+            CompleteStatus::Cancelled => CompleteStatus::Blocked,
+        }
+    }
+
+    ///Where it goes when sorting by status - the things to get on with first, done things last
+    pub fn sort_rank(&self) -> u8 {
+        match self {
+            CompleteStatus::InProgress => 0,
+            CompleteStatus::Incomplete => 1,
+            CompleteStatus::Waiting => 2,
+            CompleteStatus::Blocked => 3,
+            CompleteStatus::Complete => 4,
+            CompleteStatus::Cancelled => 5,
+        }
+    }
+
+    ///What it's called in the detail pane
+    pub fn name(&self) -> &'static str {
+        match self {
+            CompleteStatus::Incomplete => "to do",
+            CompleteStatus::Complete => "done",
+            CompleteStatus::InProgress => "in progress",
+            CompleteStatus::Blocked => "blocked",
+            CompleteStatus::Waiting => "waiting",
+            CompleteStatus::Cancelled => "cancelled",
+        }
+    }
+
+    ///Little tag shown after the title, nothing for the plain old statuses
+    pub fn tag(&self) -> &'static str {
+        match self {
+            CompleteStatus::Incomplete | CompleteStatus::Complete => "",
+            CompleteStatus::InProgress => " (doing)",
+            CompleteStatus::Blocked => " (blocked)",
+            CompleteStatus::Waiting => " (waiting)",
+            CompleteStatus::Cancelled => " (cancelled)",
+        }
+    }
+
+    ///How markdown exports show the status
+    pub fn markdown_marker(&self) -> &'static str {
+        match self {
+            CompleteStatus::Incomplete => "[ ]",
+            CompleteStatus::Complete => "[x]",
+            CompleteStatus::InProgress => "[/]",
+            CompleteStatus::Blocked => "[!]",
+            CompleteStatus::Waiting => "[?]",
+            CompleteStatus::Cancelled => "[-]",
+        }
+    }
+
+    pub fn modifier(&self) -> Modifier {
+        match self {
+            CompleteStatus::Complete => Modifier::CROSSED_OUT | Modifier::ITALIC,
+            CompleteStatus::Cancelled => Modifier::CROSSED_OUT,
+            CompleteStatus::InProgress => Modifier::BOLD,
+            CompleteStatus::Incomplete | CompleteStatus::Blocked | CompleteStatus::Waiting => Modifier::empty(),
+        }
+    }
+}
+
+///Named marks to make items stand out, each with its own colour in the theme
+#[derive(FromPrimitive, ToPrimitive, Clone, Copy, PartialEq, Debug)]
+pub enum Mark {
+    Urgent = 1,
+    Waiting = 2,
+    Review = 3,
+    Idea = 4,
+}
+
+impl Mark {
+    pub fn all() -> [Mark; 4] {
+        [Mark::Urgent, Mark::Waiting, Mark::Review, Mark::Idea]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Mark::Urgent => "urgent",
+            Mark::Waiting => "waiting",
+            Mark::Review => "review",
+            Mark::Idea => "idea",
+        }
+    }
+
+    ///The digit that picks it
+    pub fn number(&self) -> u8 {
+        *self as u8
+    }
+}
+
+///Where a dragged item lands, relative to the item it's dropped on
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DropPosition {
+    Before,
+    After,
+    ///Becomes its last child
+    Inside,
+}
+
+///An item being dragged with the mouse, and where it would land if let go now
+#[derive(Clone, Debug)]
+pub struct Drag {
+    pub item_id: u32,
+    pub target: Option<(u32, DropPosition)>,
 }
 
 pub enum MoveDirection {
@@ -54,6 +197,46 @@ impl Display for MoveDirection {
     }
 }
 
+///What to sort siblings by
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SortKey {
+    Title,
+    ///Incomplete first
+    Status,
+    ///Marked first
+    Mark,
+    ///Oldest first
+    Created,
+    ///Most important first, those without a priority last
+    Priority,
+}
+
+impl SortKey {
+    pub fn all() -> [SortKey; 5] {
+        [SortKey::Title, SortKey::Status, SortKey::Mark, SortKey::Created, SortKey::Priority]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SortKey::Title => "title",
+            SortKey::Status => "status",
+            SortKey::Mark => "mark",
+            SortKey::Created => "created",
+            SortKey::Priority => "priority",
+        }
+    }
+
+    fn compare(&self, a: &Item, b: &Item) -> Ordering {
+        match self {
+            SortKey::Title => a.title.to_lowercase().cmp(&b.title.to_lowercase()),
+            SortKey::Status => a.complete.sort_rank().cmp(&b.complete.sort_rank()),
+            SortKey::Mark => a.mark.map_or(u8::MAX, |m| m.number()).cmp(&b.mark.map_or(u8::MAX, |m| m.number())),
+            SortKey::Created => a.create_date.cmp(&b.create_date),
+            SortKey::Priority => a.priority.unwrap_or(u8::MAX).cmp(&b.priority.unwrap_or(u8::MAX)),
+        }
+    }
+}
+
 #[derive(FromPrimitive, ToPrimitive, Clone, PartialEq, PartialOrd, Debug)]
 pub enum ExpandStatus {
     Closed = 1,
@@ -62,6 +245,35 @@ pub enum ExpandStatus {
 }
 
 
+///Where the detail pane goes, if it's showing at all
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DetailPane {
+    Hidden,
+    Right,
+    Bottom,
+}
+
+impl DetailPane {
+    ///Hidden, then on the right, then along the bottom, then hidden again
+    pub fn next(&self) -> Self {
+        match self {
+            DetailPane::Hidden => DetailPane::Right,
+            DetailPane::Right => DetailPane::Bottom,
+            DetailPane::Bottom => DetailPane::Hidden,
+        }
+    }
+
+    ///The way the config file names it
+    pub fn named(name: &str) -> Option<Self> {
+        match name {
+            "hidden" => Some(DetailPane::Hidden),
+            "right" => Some(DetailPane::Right),
+            "bottom" => Some(DetailPane::Bottom),
+            _ => None,
+        }
+    }
+}
+
 ///Represent items on the rutudu list
 #[derive(Clone)]
 pub struct Item {
@@ -78,8 +290,28 @@ pub struct Item {
     pub order: u16,
     ///Not reliant on ClockRust because it made coloring too hard
     pub tracking_time: bool,
-    ///
-    pub color: Color,
+    ///Which mark it has, if any
+    pub mark: Option<Mark>,
+    ///When this should be done by, if ever
+    pub due_date: Option<NaiveDate>,
+    ///Comes back again once completed, if set
+    pub recurrence: Option<Recurrence>,
+    ///How many of all its children, grandchildren, etc are done.
+    /// Worked out when the list gets rebuilt, never saved
+    pub descendants_done: usize,
+    ///How many children, grandchildren, etc it has all together
+    pub descendants_total: usize,
+    ///Effort estimate in minutes
+    pub estimate: Option<u32>,
+    ///Estimates of this and everything under it that is not done yet, in minutes.
+    /// Worked out when the list gets rebuilt, never saved
+    pub remaining_estimate: u32,
+    ///1 is the most important, None if it was never given one
+    pub priority: Option<u8>,
+    ///When it was first added to a list
+    pub create_date: Option<NaiveDateTime>,
+    ///The tree lines in front of it, eg "│  ├─ ". Worked out when the list gets rebuilt, never saved
+    pub guide: String,
 }
 
 impl Item {
@@ -102,10 +334,35 @@ impl Item {
             depth: 0,
             order: 0,
             tracking_time: false,
-            color: Color::White,
+            mark: None,
+            due_date: None,
+            recurrence: None,
+            descendants_done: 0,
+            descendants_total: 0,
+            estimate: None,
+            remaining_estimate: 0,
+            priority: None,
+            create_date: Some(dates::now()),
+            guide: String::new(),
         }
     }
 
+    ///Take the title as typed, pulling any due:, every:, est: and pri: tokens out of it into their fields.
+    /// Fields without a token are left as they were. Returns true if there was a token
+    pub fn set_title_from_text(&mut self, text: &str, today: NaiveDate) -> bool {
+        let (title, due_date) = dates::extract_due_date(text, today);
+        let (title, recurrence) = dates::extract_recurrence(&title);
+        let (title, estimate) = dates::extract_estimate(&title);
+        let (title, priority) = dates::extract_priority(&title);
+        self.title = title;
+        let found = due_date.is_some() || recurrence.is_some() || estimate.is_some() || priority.is_some();
+        self.due_date = due_date.or(self.due_date);
+        self.recurrence = recurrence.or_else(|| self.recurrence.take());
+        self.estimate = estimate.or(self.estimate);
+        self.priority = priority.or(self.priority);
+        found
+    }
+
     ///Symbol to indicate if item is expanded or collapsed
     pub fn expansion_state_symbol(&self) -> String {
         match self.expand {
@@ -115,37 +372,164 @@ impl Item {
         }
     }
 
+    ///What comes before the expansion symbol, eg "│  ├─ 3.2: " - no number if item_no is None
+    pub fn number_prefix(&self, item_no: Option<usize>) -> String {
+        match item_no {
+            Some(n) => format!("{}{}.{}: ", self.guide, n, self.depth),
+            None => self.guide.clone(),
+        }
+    }
+
+    ///How far in the title starts, past the numbering and the expansion symbol.
+    /// The entry and any wrapped lines start here too
+    pub fn title_indent(&self, item_no: Option<usize>) -> usize {
+        self.number_prefix(item_no).width() + self.expansion_state_symbol().width() + 1
+    }
+
+    ///The tree lines carried on down past this item - a line down if more siblings come after it
+    pub fn guide_below(&self) -> String {
+        match (self.guide.strip_suffix(GUIDE_BRANCH), self.guide.strip_suffix(GUIDE_LAST)) {
+            (Some(above), _) => format!("{}{}", above, GUIDE_PIPE),
+            (_, Some(above)) => format!("{}{}", above, GUIDE_SPACE),
+            _ => self.guide.clone(),
+        }
+    }
+
+    ///What goes in front of the lines under the title - the tree lines, then space up to the title
+    pub fn continuation(&self, item_no: Option<usize>) -> String {
+        let lines = self.guide_below();
+        let pad = self.title_indent(item_no).saturating_sub(lines.width());
+        format!("{}{}", lines, " ".repeat(pad))
+    }
+
+    ///The entry a line at a time. Older lists kept the newline after the title at the front, skip that
+    pub fn entry_lines(&self) -> Vec<&str> {
+        self.entry.strip_prefix('\n').unwrap_or(&self.entry).split('\n').collect()
+    }
+
+    ///Shows how much of the subtree is done, eg [3/7] - nothing if there are no children
+    pub fn progress_symbol(&self) -> String {
+        if self.descendants_total == 0 {
+            return String::new();
+        }
+        format!("[{}/{}] ", self.descendants_done, self.descendants_total)
+    }
+
     ///Return the item as text, either just the title,
     /// or the title and the entry, depending on expand status
-    pub fn text(&self, item_no: usize) -> Vec<Spans> {
-        let modifier = match self.complete {
-            CompleteStatus::Complete => Modifier::CROSSED_OUT | Modifier::ITALIC,
-            CompleteStatus::Incomplete => Modifier::empty(),
-        };
+    pub fn text(&self, item_no: Option<usize>, theme: &Theme) -> Vec<Spans> {
+        let modifier = self.complete.modifier();
 
-        let color = if self.tracking_time {
-            Color::Red
-        }else if self.color != Color::White{//if we have a weird color, keep it
-           self.color
+        let style = if self.tracking_time {
+            theme.tracking
+        }else if let Some(mark) = self.mark {//marked, that wins over the status
+            theme.mark_style(mark)
         } else {
-            match self.complete {
-                CompleteStatus::Complete => Color::DarkGray,
-                CompleteStatus::Incomplete => Color::White,
-            }
+            theme.status_style(&self.complete)
         };
 
-        let depth_string = "--".to_string().repeat(self.depth);
-        let mut content = vec![Spans::from(
-            Span::styled(format!("{}{}.{}: {} {}", depth_string,
-                                 &item_no, &self.depth, &self.expansion_state_symbol(), self.title),
-                         Style::default().add_modifier(modifier).fg(color)))];
+        let numbers = item_no.map(|n| format!("{}.{}: ", n, self.depth)).unwrap_or_default();
+        let mut title_spans = vec![
+            Span::styled(self.guide.clone(), theme.subtle),
+            Span::styled(format!("{}{} {}{}", numbers,
+                                 &self.expansion_state_symbol(), self.progress_symbol(), self.title),
+                         style.add_modifier(modifier)),
+            Span::styled(self.complete.tag(), theme.status_style(&self.complete))];
+        if let Some(due_span) = self.due_date_span(dates::today(), theme) {
+            title_spans.push(due_span);
+        }
+        if let Some(rule) = &self.recurrence {
+            title_spans.push(Span::styled(format!(" (every:{})", rule), theme.subtle));
+        }
+        if let Some(priority) = self.priority {
+            let style = if priority == 1 {
+                theme.urgent
+            } else {
+                theme.subtle
+            };
+            title_spans.push(Span::styled(format!(" (p{})", priority), style));
+        }
+        if let Some(estimate_span) = self.estimate_span(theme) {
+            title_spans.push(estimate_span);
+        }
+        let mut content = vec![Spans::from(title_spans)];
         //show our expanded content if need be
         if let ExpandStatus::Open = self.expand {
-            content.push(Spans::from(Span::raw(format!("    {}", self.entry))));
+            let continuation = self.continuation(item_no);
+            content.extend(self.entry_lines()
+                               .into_iter()
+                               .map(|line| Spans::from(vec![Span::styled(continuation.clone(), theme.subtle),
+                                                            Span::raw(line.to_string())])));
         }
         content
     }
 
+    ///The due date, coloured by how close it is - nothing if there is no due date
+    pub fn due_date_span(&self, today: NaiveDate, theme: &Theme) -> Option<Span<'static>> {
+        let due = self.due_date?;
+        Some(Span::styled(format!(" (due {})", dates::format_date(&due)), self.due_date_style(due, today, theme)))
+    }
+
+    ///How close the due date is, in colour
+    fn due_date_style(&self, due: NaiveDate, today: NaiveDate, theme: &Theme) -> Style {
+        if self.complete.is_done() {
+            theme.done
+        } else if due < today {
+            theme.overdue
+        } else if due == today {
+            theme.due_today
+        } else if (due - today).num_days() <= dates::DUE_SOON_DAYS {
+            theme.due_soon
+        } else {
+            theme.subtle
+        }
+    }
+
+    ///The estimate, plus what is left of it under this item if it has children
+    pub fn estimate_span(&self, theme: &Theme) -> Option<Span<'static>> {
+        let own = self.estimate.map(|e| format!("est {}", dates::format_estimate(e)));
+        let left = if self.descendants_total > 0 && self.remaining_estimate > 0 {
+            Some(format!("{} left", dates::format_estimate(self.remaining_estimate)))
+        } else {
+            None
+        };
+        let text = match (own, left) {
+            (None, None) => return None,
+            (Some(o), None) => o,
+            (None, Some(l)) => l,
+            (Some(o), Some(l)) => format!("{}, {}", o, l),
+        };
+        Some(Span::styled(format!(" ({})", text), theme.subtle))
+    }
+
+    ///The item that replaces this one when a recurring item gets done.
+    /// None if it does not recur, or the next one would be due past the end of the calendar
+    pub fn next_occurrence(&self, new_id: u32, today: NaiveDate) -> Option<Item> {
+        let rule = self.recurrence.as_ref()?;
+        let due_date = match rule.next_due_date(self.due_date, today) {
+            Some(due) => due,
+            None => {
+                warn!("No date left for the next '{}' after {:?}", self.title, self.due_date);
+                return None;
+            }
+        };
+        let mut next = Item::new(new_id, &self.title, &self.entry);
+        next.parent_id = self.parent_id;
+        next.mark = self.mark;
+        next.recurrence = Some(rule.clone());
+        next.estimate = self.estimate;
+        next.priority = self.priority;
+        next.due_date = Some(due_date);
+        Some(next)
+    }
+
+    ///Recurring, not done yet, and its day has come
+    pub fn is_recurring_and_due(&self, today: NaiveDate) -> bool {
+        self.recurrence.is_some()
+            && !self.complete.is_done()
+            && self.due_date.is_none_or(|due| due <= today)
+    }
+
     //Increase expansion status from closed to show children to open
     pub fn expand(&mut self) {
         debug!("Hello");
@@ -171,10 +555,11 @@ impl Item {
         };
     }
 
+    ///Complete goes back to incomplete, everything else gets completed
     pub fn toggle_complete_status(&mut self) {
         self.complete = match self.complete {
-            CompleteStatus::Incomplete => CompleteStatus::Complete,
             CompleteStatus::Complete => CompleteStatus::Incomplete,
+            _ => CompleteStatus::Complete,
         }
     }
 
@@ -301,6 +686,47 @@ impl<T> StatefulList<T> {
         }
     }
 
+    ///Like next/previous but stops at the ends instead of going round - for the mouse wheel
+    pub fn scroll(&mut self, down: bool) {
+        if self.items.is_empty() {
+            return;
+        }
+        let i = match (self.state.selected(), down) {
+            (Some(i), true) => (i + 1).min(self.items.len() - 1),
+            (Some(i), false) => i.saturating_sub(1),
+            (None, _) => 0,
+        };
+        self.state.select(Some(i));
+    }
+
+    ///Which item is showing on this row of the list widget, counting from the top inside the border.
+    /// We draw from a fresh state every time, so tui scrolls just far enough to show the selection -
+    /// this works out where that left the top of the list
+    pub fn index_at_row<F>(&self, row: usize, visible_rows: usize, height_of: F) -> Option<usize>
+    where F: Fn(usize, &T) -> usize {
+        if self.items.is_empty() {
+            return None;
+        }
+        let selected = self.state.selected().unwrap_or(0).min(self.items.len() - 1);
+        let mut start = 0;
+        let mut height = 0;
+        for (idx, item) in self.items.iter().enumerate().take(selected + 1) {
+            height += height_of(idx, item);
+            while height > visible_rows && start < selected {
+                height -= height_of(start, &self.items[start]);
+                start += 1;
+            }
+        }
+        let mut top = 0;
+        for (idx, item) in self.items.iter().enumerate().skip(start) {
+            top += height_of(idx, item);
+            if row < top {
+                return if top <= visible_rows { Some(idx) } else { None };
+            }
+        }
+        None
+    }
+
     // pub fn unselect(&mut self) {
     //     self.    }state.select(None);
     // }
@@ -315,15 +741,55 @@ pub enum InputMode {
     InsertParent,
     InsertSibling,
     Edit,
+    ///Selecting several items at once, to do the same thing to all of them
+    Visual,
     Save,
     Open,
     Import,
+    ///Picking the list to send the selected item to
+    SendToList,
+    ///Picking where in that list it goes
+    SendToParent,
+    ///Picking what to sort by
+    Sort,
+    ///Picking a mark for the selected item(s)
+    Mark,
+    ///Picking a mark to show only the items that have it
+    MarkFilter,
     Quit,
+    ///Showing what all the keys do
+    Help,
     #[cfg(feature="clockrust")]
     PrintReport,
     DisplaySuccess,
 }
 
+impl InputMode {
+    ///What the status bar calls this mode
+    pub fn name(&self) -> &'static str {
+        match self {
+            InputMode::InsertAtRoot
+            | InputMode::InsertChild
+            | InputMode::InsertParent
+            | InputMode::InsertSibling => "INSERT",
+            InputMode::Edit => "EDIT",
+            InputMode::Visual => "VISUAL",
+            InputMode::Save => "SAVE",
+            InputMode::Open => "OPEN",
+            InputMode::Import => "IMPORT",
+            InputMode::SendToList | InputMode::SendToParent => "SEND",
+            InputMode::Sort => "SORT",
+            InputMode::Mark => "MARK",
+            InputMode::MarkFilter => "FILTER",
+            InputMode::Quit => "QUIT",
+            InputMode::Help => "HELP",
+            #[cfg(feature="clockrust")]
+            InputMode::PrintReport => "REPORT",
+            InputMode::DisplaySuccess => "DONE",
+        }
+    }
+}
+
 
 #[derive(Clone)]
 pub struct RutuduList {
@@ -345,14 +811,48 @@ pub struct RutuduList {
     pub has_scanned: bool,
 
     pub current_item: String,
-    /// This is the x,y of the cursor
-    pub cursor_position: [u16; 2],
+    /// Where the cursor is in current_item, as a byte index
+    pub input_cursor: usize,
+    /// How wide the item input box is, so we know where its lines wrap. 0 doesn't wrap
+    pub input_width: usize,
+    /// How wide the list is, so long items can wrap. 0 doesn't wrap
+    pub list_width: usize,
     /// This tells us if we need to rebuild the list
     pub dirty_list: bool,
     /// This tells us if a list has unsaved changes
     pub unsaved: bool,
-    //how far in from the end of the line are we
-    cursor_offset: u16,
+    /// Complete a parent automatically once all its children are done
+    pub auto_complete_parents: bool,
+    /// Subtree that was yanked or cut, root first - survives opening other lists
+    pub clipboard: Vec<Item>,
+    /// In visual mode, where the range selection started (index into items)
+    pub visual_anchor: Option<usize>,
+    /// In visual mode, the ids of items picked one at a time with space
+    pub picked_ids: Vec<u32>,
+    /// Message for the status bar, and when it was set so it can go away again
+    status_message: Option<(String, Instant)>,
+    /// The item whose children we show as if they were the whole list, 0 when not hoisted
+    pub hoisted_id: u32,
+    /// The list we are sending the selected item to, once picked
+    pub send_target: Option<Box<RutuduList>>,
+    /// Where in the target list the sent item can go - (parent id, indented title)
+    pub send_parent_choices: StatefulList<(u32, String)>,
+    /// How many lines the help has been scrolled down
+    pub help_scroll: u16,
+    /// What everything looks like
+    pub theme: Theme,
+    /// Only show items with this mark (and what they're under)
+    pub mark_filter: Option<Mark>,
+    /// The item being dragged with the mouse, if any
+    pub drag: Option<Drag>,
+    /// Show the numbers in front of the items
+    pub show_numbers: bool,
+    /// Where the detail pane for the selected item goes
+    pub detail_pane: DetailPane,
+    //which item the detail pane was scrolled on, and how far - another item starts back at the top
+    detail_scroll: (u32, u16),
+    //where the cursor is in the save/report dialog's text, as a byte index
+    dialog_cursor: usize,
 
 
 }
@@ -366,36 +866,152 @@ impl Default for RutuduList {
             item_tree: HashMap::new(),
             open_file_dialog_files: StatefulList::new(),
             current_item: "".to_string(),
-            cursor_position: [1, 1],
+            input_cursor: 0,
+            input_width: 0,
+            list_width: 0,
             paths: HashMap::new(),
             // file_path: String::new(),
             has_scanned: false,
             dirty_list: false,
             unsaved: false,
-            cursor_offset: 0,
+            auto_complete_parents: false,
+            clipboard: Vec::new(),
+            visual_anchor: None,
+            picked_ids: Vec::new(),
+            status_message: None,
+            hoisted_id: 0,
+            send_target: None,
+            send_parent_choices: StatefulList::new(),
+            help_scroll: 0,
+            theme: Theme::default(),
+            mark_filter: None,
+            drag: None,
+            show_numbers: true,
+            detail_pane: DetailPane::Hidden,
+            detail_scroll: (0, 0),
+            dialog_cursor: 0,
         }
     }
 }
 
 const FILE_PATH_KEY: &'static str = ":file_path";
+///Tree lines: an item with more siblings after it, the last of them,
+/// and what carries on below each of those
+const GUIDE_BRANCH: &str = "├─ ";
+const GUIDE_LAST: &str = "└─ ";
+const GUIDE_PIPE: &str = "│  ";
+const GUIDE_SPACE: &str = "   ";
+///Wrapped lines skip the indent when it would leave them less room than this
+const MIN_WRAPPED_WIDTH: usize = 10;
+///How long a status bar message stays up
+const STATUS_MESSAGE_DURATION: Duration = Duration::from_secs(4);
 #[ cfg( feature="clockrust" ) ]
 const REPORT_FILE_PATH_KEY: &'static str = ":report_file_path";
 
+///Wrap a line of styled text to fit width, carrying on in the next row after continuation.
+/// Breaks after the last space that fits, mid-word if there isn't one
+fn wrap_spans<'a>(line: Spans<'a>, width: usize, continuation: &Span<'a>) -> Vec<Spans<'a>> {
+    if width == 0 || line.width() <= width {
+        return vec![line];
+    }
+    //no room left after the continuation, don't bother with it
+    let indent = if continuation.width() + MIN_WRAPPED_WIDTH > width { 0 } else { continuation.width() };
+    let graphemes: Vec<(&str, Style)> = line.0
+        .iter()
+        .flat_map(|span| span.content.graphemes(true).map(move |g| (g, span.style)))
+        .collect();
+    //where each row starts and ends, in graphemes
+    let mut rows = Vec::new();
+    let mut start = 0;
+    let mut used = 0;
+    let mut after_space = None;
+    for (i, (g, _)) in graphemes.iter().enumerate() {
+        let room = if rows.is_empty() { width } else { width - indent };
+        if used + g.width() > room && i > start {
+            let wrap_at = after_space.unwrap_or(i);
+            rows.push((start, wrap_at));
+            used = graphemes[wrap_at..i].iter().map(|(g, _)| g.width()).sum();
+            start = wrap_at;
+            after_space = None;
+        }
+        used += g.width();
+        if *g == " " {
+            after_space = Some(i + 1);
+        }
+    }
+    rows.push((start, graphemes.len()));
+
+    rows.into_iter()
+        .enumerate()
+        .map(|(row_no, (start, end))| {
+            //put the graphemes back together into spans of the same style
+            let mut spans: Vec<Span> = Vec::new();
+            for (g, style) in &graphemes[start..end] {
+                match spans.last_mut() {
+                    Some(span) if span.style == *style => span.content.to_mut().push_str(g),
+                    _ => spans.push(Span::styled(g.to_string(), *style)),
+                }
+            }
+            if row_no > 0 && indent > 0 {
+                spans.insert(0, continuation.clone());
+            }
+            Spans::from(spans)
+        })
+        .collect()
+}
+
+///Where the character before byte idx starts. Characters here are what we see as one -
+/// an emoji with its skin tone, a letter with its accent - not chars
+fn prev_grapheme(text: &str, idx: usize) -> usize {
+    text[..idx].grapheme_indices(true).next_back().map_or(0, |(i, _)| i)
+}
+
+///Where the character after byte idx ends
+fn next_grapheme(text: &str, idx: usize) -> usize {
+    text[idx..].graphemes(true).next().map_or(idx, |g| idx + g.len())
+}
+
 impl RutuduList {
     pub fn enter_edit_mode(&mut self) {
-        self.cursor_position = [1, 1];
         self.input_mode = InputMode::Edit;
     }
 
+    ///Start selecting several items. With a range, everything between here and
+    /// wherever j/k take us gets selected, otherwise this item is picked by itself
+    pub fn enter_visual_mode(&mut self, with_range: bool) {
+        if self.items.items.is_empty() {
+            return;
+        }
+        if self.items.state.selected().is_none() {
+            self.items.state.select(Some(0));
+        }
+        self.visual_anchor = None;
+        self.picked_ids.clear();
+        self.input_mode = InputMode::Visual;
+        if with_range {
+            self.toggle_visual_range();
+        } else {
+            self.toggle_pick_selected();
+        }
+    }
+
+    ///Leave visual mode, forgetting what was selected
+    pub fn exit_visual_mode(&mut self) {
+        self.visual_anchor = None;
+        self.picked_ids.clear();
+        self.enter_edit_mode();
+    }
+
     pub fn enter_save_mode(&mut self) {
-        self.cursor_position = [self.file_path().len() as u16, 1];
-        self.cursor_offset = 0;
+        self.dialog_cursor = self.file_path().len();
         self.input_mode = InputMode::Save;
     }
 
     pub fn save(&mut self){
         db::save_list(self).expect("Could not save list");
         self.mark_saved();
+        let msg = format!("Saved {}", self.list_name());
+        self.set_status_message(&msg);
     }
     pub fn mark_saved(&mut self) {
         self.unsaved = false;
@@ -421,8 +1037,7 @@ impl RutuduList {
         let fp_without_ext = self.file_path().replace(".rtd", "");
         let rname = format!("{}{}_{}.{}", fp_without_ext, DEFAULT_REPORT_PATH, date_part, "txt");
         self.set_report_file_path( &rname );
-        self.cursor_position[0]= rname.len()  as u16 + 1;
-        self.cursor_offset=0;
+        self.dialog_cursor = rname.len();
     }
 
     #[cfg(feature="clockrust")]
@@ -450,6 +1065,7 @@ impl RutuduList {
 
     ///Create an item at the current level
     pub fn enter_insert_mode(&mut self, mode: InputMode) {
+        self.input_cursor = self.current_item.len();
         self.input_mode = mode;
     }
 
@@ -462,9 +1078,139 @@ impl RutuduList {
         self.input_mode = InputMode::Import;
     }
 
+    ///Show what all the keys do
+    pub fn enter_help_mode(&mut self){
+        self.help_scroll = 0;
+        self.input_mode = InputMode::Help;
+    }
+
+    pub fn help_down(&mut self){
+        self.help_scroll = self.help_scroll.saturating_add(1);
+    }
+
+    pub fn help_up(&mut self){
+        self.help_scroll = self.help_scroll.saturating_sub(1);
+    }
+
+    ///Show dialog to pick a mark. Keeps any visual selection, the mark goes on all of it
+    pub fn enter_mark_mode(&mut self){
+        if self.selected_item().is_none() {
+            return;
+        }
+        self.input_mode = InputMode::Mark;
+    }
+
+    ///Changed our mind about marking - back to visual mode if that's where we came from
+    pub fn exit_mark_mode(&mut self){
+        if self.has_visual_selection() {
+            self.input_mode = InputMode::Visual;
+        } else {
+            self.enter_edit_mode();
+        }
+    }
+
+    ///Show dialog to pick the mark to filter by
+    pub fn enter_mark_filter_mode(&mut self){
+        self.input_mode = InputMode::MarkFilter;
+    }
+
+    ///Show dialog to pick what to sort by
+    pub fn enter_sort_mode(&mut self){
+        self.input_mode = InputMode::Sort;
+    }
+
+    ///Show file dialog to pick the list the selected item gets sent to
+    pub fn enter_send_to_list_mode(&mut self){
+        if self.selected_item().is_none() {
+            return;
+        }
+        self.send_target = None;
+        self.input_mode = InputMode::SendToList;
+    }
+
+    ///Load the list picked in the file dialog, then let us pick a parent in it
+    pub fn choose_send_target_from_file_dialog(&mut self){
+        let s = self.open_file_dialog_files.state.clone();
+        let filename = match self.open_file_dialog_files.items.get(s.selected().unwrap_or(0)) {
+            Some(f) => f.clone(),
+            None => return,
+        };
+        if filename.trim_start_matches("./") == self.file_path().trim_start_matches("./") {
+            warn!("Not sending an item to the list it is already in");
+            self.enter_edit_mode();
+            return;
+        }
+        let mut target = RutuduList::default();
+        if let Err(why) = db::load_list(&mut target, &filename) {
+            error!("Failed to load list {} to send to: {}", filename, why);
+            self.enter_edit_mode();
+            return;
+        }
+        //anything in the target can be the new parent, whether it's expanded or not
+        self.send_parent_choices = StatefulList::new();
+        self.send_parent_choices.items.push((0, String::from("<top level>")));
+        target.collect_parent_choices(0, 0, &mut self.send_parent_choices.items);
+        self.send_parent_choices.state.select(Some(0));
+        self.send_target = Some(Box::new(target));
+        self.input_mode = InputMode::SendToParent;
+    }
+
+    ///Every item under this parent as (id, title indented by depth), parents before their children
+    fn collect_parent_choices(&self, parent_id: u32, depth: usize, choices: &mut Vec<(u32, String)>) {
+        if let Some(children) = self.item_tree.get(&parent_id) {
+            for child in children {
+                choices.push((child.id, format!("{}{}", "  ".repeat(depth + 1), child.title)));
+                self.collect_parent_choices(child.id, depth + 1, choices);
+            }
+        }
+    }
+
+    ///Put the selected item and everything under it into the target list under the picked parent,
+    /// save the target and take the item out of this list
+    pub fn send_selected_to_target(&mut self){
+        let parent_idx = self.send_parent_choices.state.selected().unwrap_or(0);
+        let parent_id = self.send_parent_choices.items.get(parent_idx).map_or(0, |(id, _)| *id);
+        let subtree = match self.selected_item() {
+            Some(item) => self.collect_subtree(item.id),
+            None => Vec::new(),
+        };
+        if let (Some(mut target), false) = (self.send_target.take(), subtree.is_empty()) {
+            if parent_id != 0 {
+                if let Some(parent) = target.item_tree.values_mut().flat_map(|v| v.iter_mut()).find(|i| i.id == parent_id) {
+                    parent.show_children();
+                }
+            }
+            target.graft_subtree(&subtree, parent_id, None);
+            match db::save_list(&target) {
+                Ok(_) => {
+                    debug!("Sent {} items to {}", subtree.len(), target.file_path());
+                    self.erase_selected();
+                    self.unsaved = true;
+                    self.set_status_message(&format!("Sent {} item(s) to {}", subtree.len(), target.file_path()));
+                }
+                Err(why) => {
+                    error!("Failed to save {}, item not sent: {}", target.file_path(), why);
+                    self.set_status_message(&format!("Could not send to {}", target.file_path()));
+                }
+            }
+        }
+        self.enter_edit_mode();
+    }
+
+    pub fn send_parent_up(&mut self) {
+        self.send_parent_choices.previous();
+    }
+
+    pub fn send_parent_down(&mut self) {
+        self.send_parent_choices.next();
+    }
+
     ///Collapse selected item (don't show children, don't show description)
     /// i.e. reduce expansion status
     pub fn collapse_selected(&mut self) {
+        if self.selected_item().is_none() {
+            return;
+        }
         let i = self.items.state.selected().unwrap_or(0);
         //expand the parent
         //get the parent id and then get the item and set its expansion status
@@ -601,7 +1347,7 @@ impl RutuduList {
                         // self.dirty_list = true;
                     }
                     MoveDirection::Out => {//make your grandparent your parent id and put yourself in the right bucket
-                        if parent_id == 0 {//if it's at the root level, we are as far out (man) as we can go
+                        if parent_id == 0 || parent_id == self.hoisted_id {//if it's at the root level, we are as far out (man) as we can go
                             return;
                         }
                         //how to find one's parent....
@@ -665,6 +1411,9 @@ impl RutuduList {
 
     ///Moves expansion status up the scale
     pub fn expand_selected(&mut self) {
+        if self.selected_item().is_none() {
+            return;
+        }
         let i = self.items.state.selected().unwrap_or(0);
         let list_size = self.items.items.len();
         debug!("Expanding item with selected index {} on list of size {}", i, list_size);
@@ -695,6 +1444,91 @@ impl RutuduList {
         self.dirty_list = true;
     }
 
+    ///Walk the tree under parent_id, letting f set each item's expansion,
+    /// given the item, its depth and whether it has children
+    fn set_expansion_below<F>(&mut self, parent_id: u32, depth: usize, f: &F)
+    where F: Fn(&mut Item, usize, bool) {
+        let child_ids: Vec<u32> = match self.item_tree.get(&parent_id) {
+            Some(children) => children.iter().map(|c| c.id).collect(),
+            None => return,
+        };
+        for id in child_ids {
+            let has_children = self.item_tree.get(&id).is_some_and(|c| !c.is_empty());
+            if let Some(item) = self.item_tree
+                                    .get_mut(&parent_id)
+                                    .and_then(|b| b.iter_mut().find(|i| i.id == id)) {
+                f(item, depth, has_children);
+            }
+            self.set_expansion_below(id, depth + 1, f);
+        }
+    }
+
+    ///After expanding/collapsing lots, select the item we had - or the closest ancestor still showing
+    fn reselect_after_expansion(&mut self, id: Option<u32>) {
+        self.rebuild_list();
+        let mut id = match id {
+            Some(id) => id,
+            None => return,
+        };
+        while id != 0 {
+            if let Some(idx) = self.items.items.iter().position(|i| i.id == id) {
+                self.items.state.select(Some(idx));
+                return;
+            }
+            id = self.parent_id_of(id).unwrap_or(0);
+        }
+        self.items.state.select(Some(0));
+    }
+
+    ///Close every item in the list
+    pub fn collapse_all(&mut self) {
+        let selected_id = self.selected_item().map(|i| i.id);
+        self.set_expansion_below(0, 0, &|item, _, _| item.expand = ExpandStatus::Closed);
+        self.reselect_after_expansion(selected_id);
+    }
+
+    ///Expand every item in the list. ShowChildren only touches items with children,
+    /// Open shows every entry as well
+    pub fn expand_all(&mut self, status: ExpandStatus) {
+        let selected_id = self.selected_item().map(|i| i.id);
+        self.set_expansion_below(0, 0, &|item, _, has_children| {
+            if has_children || status == ExpandStatus::Open {
+                item.expand = status.clone();
+            }
+        });
+        self.reselect_after_expansion(selected_id);
+    }
+
+    ///Show the children of the selected item, their children, and so on all the way down
+    pub fn expand_selected_subtree(&mut self) {
+        let item = match self.selected_item() {
+            Some(item) => item.clone(),
+            None => return,
+        };
+        if let Some(tree_item) = self.get_item_in_tree_mut(&item) {
+            tree_item.show_children();
+        }
+        self.set_expansion_below(item.id, 0, &|item, _, has_children| {
+            if has_children {
+                item.show_children();
+            }
+        });
+        self.reselect_after_expansion(Some(item.id));
+    }
+
+    ///Show the tree down to this many levels - 1 is only the root items
+    pub fn expand_to_depth(&mut self, levels: usize) {
+        let selected_id = self.selected_item().map(|i| i.id);
+        self.set_expansion_below(0, 0, &|item, depth, has_children| {
+            if depth + 1 < levels && has_children {
+                item.show_children();
+            } else {
+                item.expand = ExpandStatus::Closed;
+            }
+        });
+        self.reselect_after_expansion(selected_id);
+    }
+
     //Will erase the selected item - including its children.
     pub fn erase_selected(&mut self){
         let i = if let Some(index) = self.items.state.selected(){
@@ -706,7 +1540,8 @@ impl RutuduList {
         let item_id = self.items.items[i].id;
         let parent_id = self.items.items[i].parent_id;
 
-        self.item_tree.remove(&item_id);
+        //grandchildren have buckets too, they need to go as well
+        self.remove_descendants(item_id);
         let parent_vec = self.item_tree.get_mut(&parent_id);
         if let Some(v) = parent_vec{
            if let Some(idx) = v.iter().position(|item| item.id ==item_id){
@@ -721,20 +1556,242 @@ impl RutuduList {
 
     }
 
-    ///Delete's the selected item, but does not delete children:
-    /// they get attached to parent
-    pub fn delete_selected(&mut self) {
-        let i = if let Some(index) = self.items.state.selected() {
-            index
-        } else {
-            return;//nothing selected, nothing to delete
+    ///Drop the buckets of all the children, grandchildren, etc of this id
+    fn remove_descendants(&mut self, item_id: u32) {
+        if let Some(children) = self.item_tree.remove(&item_id) {
+            children.iter().for_each(|c| self.remove_descendants(c.id));
+        }
+    }
+
+    ///The item with this id followed by all its children, grandchildren, etc,
+    /// parents always before their children and siblings in order
+    pub fn collect_subtree(&self, item_id: u32) -> Vec<Item> {
+        let mut subtree = Vec::new();
+        let root = self.parent_id_of(item_id)
+                       .and_then(|parent_id| self.item_tree[&parent_id].iter().find(|i| i.id == item_id));
+        if let Some(root) = root {
+            subtree.push(root.clone());
+            self.collect_descendants(item_id, &mut subtree);
+        }
+        subtree
+    }
+
+    fn collect_descendants(&self, parent_id: u32, subtree: &mut Vec<Item>) {
+        if let Some(children) = self.item_tree.get(&parent_id) {
+            for child in children {
+                subtree.push(child.clone());
+                self.collect_descendants(child.id, subtree);
+            }
+        }
+    }
+
+    ///Put a subtree (as from collect_subtree) into the list under the new parent,
+    /// straight after the sibling with after_id if given, at the end of the bucket otherwise.
+    /// Everything gets fresh ids, and we return the new id of the subtree's root.
+    /// The copies aren't clocked in, and get created when they're saved
+    pub fn graft_subtree(&mut self, subtree: &[Item], parent_id: u32, after_id: Option<u32>) -> u32 {
+        let first_id = self.get_max_id() + 1;
+        let mut new_ids: HashMap<u32, u32> = HashMap::new();
+        for (idx, item) in subtree.iter().enumerate() {
+            let mut new_item = item.clone();
+            new_item.id = first_id + idx as u32;
+            new_item.tracking_time = false;
+            new_item.create_date = None;
+            new_ids.insert(item.id, new_item.id);
+            if idx == 0 {
+                new_item.parent_id = parent_id;
+                let bucket = self.item_tree.entry(parent_id).or_default();
+                match after_id.and_then(|a| bucket.iter().position(|i| i.id == a)) {
+                    Some(pos) => bucket.insert(pos + 1, new_item),
+                    None => bucket.push(new_item),
+                }
+            } else {
+                //parents come first, so they've always been given their new id already
+                new_item.parent_id = new_ids[&item.parent_id];
+                self.item_tree.entry(new_item.parent_id).or_default().push(new_item);
+            }
+        }
+        self.dirty_list = true;
+        self.unsaved = true;
+        new_ids.get(&subtree[0].id).copied().unwrap_or(0)
+    }
+
+    ///Copy the selected item and everything under it, ready to paste
+    pub fn yank_selected(&mut self) {
+        if let Some(item) = self.selected_item() {
+            let subtree = self.collect_subtree(item.id);
+            debug!("Yanked {} items", subtree.len());
+            self.set_status_message(&format!("Yanked {} item(s)", subtree.len()));
+            self.clipboard = subtree;
+        }
+    }
+
+    ///Deep copy the selected item and everything under it, right after the original.
+    /// Leaves whatever was yanked alone
+    pub fn duplicate_selected(&mut self) {
+        let (id, parent_id) = match self.selected_item() {
+            Some(item) => (item.id, item.parent_id),
+            None => return,
         };
+        let subtree = self.collect_subtree(id);
+        if subtree.is_empty() {
+            return;
+        }
+        let new_id = self.graft_subtree(&subtree, parent_id, Some(id));
+        self.select_item(new_id);
+    }
 
-        //find item to delete
-        let item_id = self.items.items[i].id;
-        //find its parent
-        let grand_parent_id = self.items.items[i].parent_id;
-        self.delete_item(grand_parent_id, item_id);
+    ///Yank the selected item and everything under it, then take it out of the list
+    pub fn cut_selected(&mut self) {
+        self.yank_selected();
+        if !self.clipboard.is_empty() {
+            self.erase_selected();
+            self.unsaved = true;
+        }
+    }
+
+    ///Paste what was yanked (or cut) as the sibling after the selected item,
+    /// or as its last child. With nothing selected it goes at the root
+    pub fn paste(&mut self, as_child: bool) {
+        if self.clipboard.is_empty() {
+            return;
+        }
+        let (parent_id, after_id) = match self.selected_item() {
+            None => (self.hoisted_id, None),
+            Some(selected) if as_child => (selected.id, None),
+            Some(selected) => (selected.parent_id, Some(selected.id)),
+        };
+        if as_child {
+            if let Some(selected) = self.selected_item().cloned() {
+                if let Some(tree_item) = self.get_item_in_tree_mut(&selected) {
+                    tree_item.show_children();
+                }
+            }
+        }
+        let subtree = self.clipboard.clone();
+        let new_id = self.graft_subtree(&subtree, parent_id, after_id);
+        self.select_item(new_id);
+    }
+
+    ///Start a range selection at the selected item, or drop the one we have
+    pub fn toggle_visual_range(&mut self) {
+        self.visual_anchor = match self.visual_anchor {
+            Some(_) => None,
+            None => self.items.state.selected(),
+        };
+    }
+
+    ///Pick the selected item on its own, or unpick it
+    pub fn toggle_pick_selected(&mut self) {
+        if let Some(id) = self.selected_item().map(|i| i.id) {
+            match self.picked_ids.iter().position(|p| *p == id) {
+                Some(pos) => { self.picked_ids.remove(pos); }
+                None => self.picked_ids.push(id),
+            }
+        }
+    }
+
+    ///Is there a range or any picked items?
+    pub fn has_visual_selection(&self) -> bool {
+        self.visual_anchor.is_some() || !self.picked_ids.is_empty()
+    }
+
+    ///The ids of everything selected in visual mode, in the order they're shown
+    pub fn visual_selection_ids(&self) -> Vec<u32> {
+        self.items.items
+            .iter()
+            .enumerate()
+            .filter(|(idx, item)| self.is_visually_selected(*idx, item))
+            .map(|(_, item)| item.id)
+            .collect()
+    }
+
+    ///Is this item part of the visual selection?
+    pub fn is_visually_selected(&self, idx: usize, item: &Item) -> bool {
+        //still selected while picking a mark for them
+        if !matches!(self.input_mode, InputMode::Visual | InputMode::Mark) {
+            return false;
+        }
+        let in_range = match (self.visual_anchor, self.items.state.selected()) {
+            (Some(anchor), Some(cursor)) => idx >= anchor.min(cursor) && idx <= anchor.max(cursor),
+            _ => false,
+        };
+        in_range || self.picked_ids.contains(&item.id)
+    }
+
+    ///Do the same thing to every item selected in visual mode, one at a time, then go back to edit mode.
+    /// Bottom up if it must be, eg moving out, to keep the items in their order
+    pub fn apply_to_visual_selection<F>(&mut self, bottom_up: bool, op: F)
+    where F: Fn(&mut RutuduList) {
+        let mut ids = self.visual_selection_ids();
+        if bottom_up {
+            ids.reverse();
+        }
+        debug!("Applying to {} selected items", ids.len());
+        for id in ids {
+            //an earlier op may have taken it away (eg erasing its parent) or hidden it
+            self.rebuild_list();
+            if let Some(idx) = self.items.items.iter().position(|i| i.id == id) {
+                self.items.state.select(Some(idx));
+                op(self);
+            }
+        }
+        self.rebuild_list_if_dirty();
+        self.exit_visual_mode();
+    }
+
+    ///Sort the children of the selected item - or, if it has none, the siblings it lives among.
+    /// With nothing selected it's the root list that gets sorted.
+    /// Recursive sorts the children of the children as well, all the way down
+    pub fn sort_selected(&mut self, key: SortKey, recursive: bool) {
+        let bucket_id = match self.selected_item() {
+            None => self.hoisted_id,
+            Some(item) if self.item_tree.get(&item.id).is_some_and(|c| !c.is_empty()) => item.id,
+            Some(item) => item.parent_id,
+        };
+        debug!("Sorting bucket {} by {:?}, recursive={}", bucket_id, key, recursive);
+        let selected_id = self.selected_item().map(|i| i.id);
+        self.sort_bucket(bucket_id, key, recursive);
+        self.unsaved = true;
+        self.set_status_message(&format!("Sorted by {:?}", key));
+        self.input_mode = InputMode::Edit;
+        match selected_id {
+            Some(id) => self.select_item(id),
+            None => self.dirty_list = true,
+        }
+    }
+
+    fn sort_bucket(&mut self, bucket_id: u32, key: SortKey, recursive: bool) {
+        let child_ids: Vec<u32> = match self.item_tree.get_mut(&bucket_id) {
+            None => return,
+            Some(bucket) => {
+                //stable, so ties keep the order they had
+                bucket.sort_by(|a, b| key.compare(a, b));
+                bucket.iter_mut()
+                      .enumerate()
+                      .for_each(|(idx, item)| item.order = idx as u16);
+                bucket.iter().map(|i| i.id).collect()
+            }
+        };
+        if recursive {
+            child_ids.iter().for_each(|id| self.sort_bucket(*id, key, recursive));
+        }
+    }
+
+    ///Delete's the selected item, but does not delete children:
+    /// they get attached to parent
+    pub fn delete_selected(&mut self) {
+        let i = if let Some(index) = self.items.state.selected() {
+            index
+        } else {
+            return;//nothing selected, nothing to delete
+        };
+
+        //find item to delete
+        let item_id = self.items.items[i].id;
+        //find its parent
+        let grand_parent_id = self.items.items[i].parent_id;
+        self.delete_item(grand_parent_id, item_id);
 
         // //find its children and set the parent ids of the children to the parents parent
         // self.item_tree
@@ -821,6 +1878,37 @@ impl RutuduList {
         self.dirty_list = true;
     }
 
+    ///How many items in the whole list are done
+    pub fn done_count(&self) -> usize {
+        self.item_tree
+            .values()
+            .flat_map(|v| v.iter())
+            .filter(|i| i.complete.is_done())
+            .count()
+    }
+
+    ///Title of the item we're tracking time on, if any
+    pub fn tracked_item_title(&self) -> Option<String> {
+        self.item_tree
+            .values()
+            .flat_map(|v| v.iter())
+            .find(|i| i.tracking_time)
+            .map(|i| i.title.clone())
+    }
+
+    ///Show a message in the status bar for a little while
+    pub fn set_status_message(&mut self, msg: &str) {
+        self.status_message = Some((msg.to_string(), Instant::now()));
+    }
+
+    ///The status bar message, if it hasn't been showing for too long yet
+    pub fn status_message(&self) -> Option<&str> {
+        match &self.status_message {
+            Some((msg, shown_at)) if shown_at.elapsed() < STATUS_MESSAGE_DURATION => Some(msg),
+            _ => None,
+        }
+    }
+
     ///Return the number of items in this list (whether shown or not)
     pub fn size(&self) -> usize{
         //add up the number of items in each of buckets
@@ -852,6 +1940,7 @@ impl RutuduList {
     }
 
     fn toggle_item(&mut self, i:usize) -> CompleteStatus {
+        let next_id = self.get_max_id() + 1;
         if let Some(item) = self.items.items.get_mut(i) {
             item.toggle_complete_status();
             //mark it on the tree
@@ -860,14 +1949,117 @@ impl RutuduList {
                               .filter(|i| i.id == item.id)
                               .for_each(|i| i.toggle_complete_status());
             };
+            self.dirty_list = true;
             self.unsaved = true;
-            item.complete.clone()
+            let status = item.complete.clone();
+            //recurring items get their next occurrence when they are done. The rule moves on to it,
+            // so toggling the done one again doesn't spawn another
+            if let CompleteStatus::Complete = status {
+                let (done_id, parent_id) = (item.id, item.parent_id);
+                if let Some(next) = item.next_occurrence(next_id, dates::today()) {
+                    item.recurrence = None;
+                    if let Some(tree_item) = self.item_tree.get_mut(&parent_id)
+                                                 .and_then(|bucket| bucket.iter_mut().find(|i| i.id == done_id)) {
+                        tree_item.recurrence = None;
+                    }
+                    self.insert_after(done_id, next);
+                }
+                if self.auto_complete_parents {
+                    self.complete_finished_ancestors(parent_id);
+                }
+            }
+            status
         } else {
             warn!("Tried to toggle complete status with nothing selected");
             CompleteStatus::Incomplete
         }
     }
 
+    ///Move the selected item on to the next (or previous) of the
+    /// in progress, waiting, blocked and cancelled statuses
+    pub fn cycle_selected_item_status(&mut self, forward: bool) {
+        let item = match self.selected_item() {
+            Some(item) => item.clone(),
+            None => return,
+        };
+        if let Some(tree_item) = self.get_item_in_tree_mut(&item) {
+            tree_item.complete = if forward {
+                tree_item.complete.next()
+            } else {
+                tree_item.complete.previous()
+            };
+            debug!("Item {} is now {:?}", tree_item.id, tree_item.complete);
+        }
+        self.dirty_list = true;
+        self.unsaved = true;
+    }
+
+    ///Starting at the given parent and working up, complete every parent whose children are all done
+    fn complete_finished_ancestors(&mut self, parent_id: u32) {
+        let mut parent_id = parent_id;
+        while parent_id != 0 {
+            let all_done = self.item_tree
+                               .get(&parent_id)
+                               .is_some_and(|children| children.iter().all(|c| c.complete.is_done()));
+            if !all_done {
+                return;
+            }
+            let grand_parent_id = match self.parent_id_of(parent_id) {
+                Some(id) => id,
+                None => return,
+            };
+            if let Some(parent) = self.item_tree
+                                      .entry(grand_parent_id)
+                                      .or_default()
+                                      .iter_mut()
+                                      .find(|i| i.id == parent_id) {
+                if parent.complete.is_done() {
+                    return;
+                }
+                debug!("All children of {} are done, completing it", parent_id);
+                parent.complete = CompleteStatus::Complete;
+            }
+            parent_id = grand_parent_id;
+        }
+        self.dirty_list = true;
+    }
+
+    ///Find out which bucket the item with this id lives in
+    pub fn parent_id_of(&self, id: u32) -> Option<u32> {
+        self.item_tree
+            .values()
+            .flat_map(|v| v.iter())
+            .find(|i| i.id == id)
+            .map(|i| i.parent_id)
+    }
+
+    ///Count how many of the children, grandchildren, etc under this id are done, and how many there are
+    pub fn count_descendants(&self, parent_id: u32) -> (usize, usize) {
+        match self.item_tree.get(&parent_id) {
+            None => (0, 0),
+            Some(children) => children.iter()
+                                      .fold((0, 0), |(done, total), child| {
+                                          let (sub_done, sub_total) = self.count_descendants(child.id);
+                                          let child_done = if child.complete.is_done() { 1 } else { 0 };
+                                          (done + child_done + sub_done, total + 1 + sub_total)
+                                      }),
+        }
+    }
+
+    ///Add up the estimates of everything under this id that is not done yet, in minutes
+    pub fn estimate_remaining(&self, parent_id: u32) -> u32 {
+        match self.item_tree.get(&parent_id) {
+            None => 0,
+            //huge estimates just max out rather than wrap around
+            Some(children) => children.iter()
+                                      .map(|child| {
+                                          let own = if child.complete.is_done() { 0 } else { child.estimate.unwrap_or(0) };
+                                          own.saturating_add(self.estimate_remaining(child.id))
+                                      })
+                                      .fold(0, u32::saturating_add),
+        }
+    }
+
     ///Load the list selected in the open file dialog
     pub fn load_list_from_file_dialog(&mut self) {
         let s = self.open_file_dialog_files.state.clone();
@@ -904,11 +2096,52 @@ impl RutuduList {
             .iter()
             .enumerate()
             .map(|(i, msg)| {
-                let content = msg.text(i);
-                ListItem::new(content)
+                let content = self.item_lines(i, msg);
+                if self.is_visually_selected(i, msg) {
+                    ListItem::new(content).style(self.theme.selection)
+                } else {
+                    ListItem::new(content)
+                }
             }).collect()
     }
 
+    ///The rows an item takes up in the list, wrapped to fit
+    fn item_lines<'a>(&'a self, i: usize, item: &'a Item) -> Vec<Spans<'a>> {
+        let mut content = item.text(self.item_number(i), &self.theme);
+        //show where a dragged item would land
+        if let Some((_, position)) = self.drag.as_ref()
+                                         .and_then(|d| d.target)
+                                         .filter(|(target_id, _)| *target_id == item.id) {
+            let hint = match position {
+                DropPosition::Before => " <- drop above",
+                DropPosition::After => " <- drop below",
+                DropPosition::Inside => " <- drop inside",
+            };
+            if let Some(first_line) = content.first_mut() {
+                first_line.0.push(Span::styled(hint, self.theme.drop_target));
+            }
+        }
+        let continuation = Span::styled(item.continuation(self.item_number(i)), self.theme.subtle);
+        content.into_iter()
+               .flat_map(|line| wrap_spans(line, self.list_width, &continuation))
+               .collect()
+    }
+
+    ///The number shown in front of the item at index i, if we're showing them
+    pub fn item_number(&self, i: usize) -> Option<usize> {
+        if self.show_numbers { Some(i) } else { None }
+    }
+
+    ///Show or hide the numbers in front of the items
+    pub fn toggle_numbers(&mut self) {
+        self.show_numbers = !self.show_numbers;
+    }
+
+    ///How many rows of the list an item takes up, wrapped lines and all
+    pub fn item_height(&self, i: usize, item: &Item) -> usize {
+        self.item_lines(i, item).len()
+    }
+
     ///If the list is dirty, we create a new one from the hashmap
     pub fn rebuild_list_if_dirty(&mut self) {
         if self.dirty_list {
@@ -923,7 +2156,8 @@ impl RutuduList {
             return;
         }
 
-        let root_items_vec = self.get_subtree_vec(0, 0);
+        //when hoisted, only what's under the hoisted item
+        let root_items_vec = self.get_subtree_vec(self.hoisted_id, 0);
         self.items.items.clear();
         root_items_vec.iter().enumerate().for_each(|(_, item)| {
             // let new_item = ListItem::new(item.text(i));
@@ -932,15 +2166,34 @@ impl RutuduList {
     }
 
     pub fn get_subtree_vec(&self, parent_id: u32, depth: usize) -> Vec<Item> {
+        self.subtree_with_guides(parent_id, depth, "")
+    }
+
+    ///The subtree with the tree lines worked out - lines_above is what carries on down from further up.
+    /// The top level doesn't get any lines, there's nothing for them to join
+    fn subtree_with_guides(&self, parent_id: u32, depth: usize, lines_above: &str) -> Vec<Item> {
         let mut ret_list = Vec::new();
         if self.item_tree.contains_key(&parent_id) {
-            let item_subtree_vec: Vec<Item> = self.item_tree[&parent_id].clone();
-            for mut item in item_subtree_vec {
+            //when filtering by mark, only the marked items and the path down to them
+            let shown: Vec<(Item, bool)> = self.item_tree[&parent_id]
+                .iter()
+                .map(|item| (item.clone(), self.mark_filter.is_some_and(|m| self.subtree_has_mark(item.id, m))))
+                .filter(|(item, leads_to_mark)| self.mark_filter.is_none_or(|m| item.mark == Some(m)) || *leads_to_mark)
+                .collect();
+            let last = shown.len().saturating_sub(1);
+            for (n, (mut item, leads_to_mark)) in shown.into_iter().enumerate() {
                 item.depth = depth;
+                item.guide = match (depth, n == last) {
+                    (0, _) => String::new(),
+                    (_, false) => format!("{}{}", lines_above, GUIDE_BRANCH),
+                    (_, true) => format!("{}{}", lines_above, GUIDE_LAST),
+                };
+                (item.descendants_done, item.descendants_total) = self.count_descendants(item.id);
+                let own = if item.complete.is_done() { 0 } else { item.estimate.unwrap_or(0) };
+                item.remaining_estimate = self.estimate_remaining(item.id).saturating_add(own);
                 ret_list.push(item.clone());
-                if item.should_show_children() {
-                    let sub_sub_tree_vec = self.get_subtree_vec(item.id, depth + 1);
-                    sub_sub_tree_vec.iter().for_each(|i| { ret_list.push(i.clone()) })
+                if item.should_show_children() || leads_to_mark {
+                    ret_list.extend(self.subtree_with_guides(item.id, depth + 1, &item.guide_below()));
                 }
             }
         }
@@ -983,13 +2236,15 @@ impl RutuduList {
                     if let Some(i) = self.items.state.selected() {
                         //children lists are by implication - mapped by item.id in the hashmap
                         (self.items.items[i].id.clone(), self.items.items[i].id.clone())
-                    } else { (0, 0) }
+                    } else { (self.hoisted_id, 0) }
                 }
                 InputMode::InsertSibling => {//parent is parent of node we selected
                     if let Some(i) = self.items.state.selected() {
                         (self.items.items[i].parent_id.clone(), self.items.items[i].id.clone())
-                    } else { (0, 0) }
+                    } else { (self.hoisted_id, 0) }
                 }
+                //when hoisted, the hoisted item is as rooty as it gets
+                InputMode::InsertAtRoot => { (self.hoisted_id, 0) }
                 InputMode::InsertParent => {//
                     //we make it it's own parent, after insertion we're going to swap it
                     //with the selected item and swap their parent ids
@@ -1114,6 +2369,20 @@ impl RutuduList {
             .find(|i| { i.id == item.id })
     }
 
+    ///Put an item into its parent's bucket, right after the sibling with the given id,
+    /// or at the end if that sibling is not there
+    fn insert_after(&mut self, sibling_id: u32, item: Item) {
+        let bucket = self.item_tree
+                         .entry(item.parent_id)
+                         .or_default();
+        match bucket.iter().position(|i| i.id == sibling_id) {
+            Some(pos) => bucket.insert(pos + 1, item),
+            None => bucket.push(item),
+        }
+        self.dirty_list = true;
+        self.unsaved = true;
+    }
+
     ///Get the maximum id of the items in the list
     pub fn get_max_id(&self)->u32{
         let mut max_id = 0;
@@ -1131,117 +2400,272 @@ impl RutuduList {
 
     pub fn get_current_input_as_item(&mut self) -> Item {
         let mut entry: String = self.current_item.drain(..).collect();
+        self.input_cursor = 0;
         //split by newlines
         let first_new_line = entry.find('\n').unwrap_or_else(||entry.len());
         let title: String = entry.drain(..first_new_line).collect();
+        //the newline between the title and the entry isn't part of either
+        if entry.starts_with('\n') {
+            entry.remove(0);
+        }
         let max_id = self.get_max_id();
         debug!("Next item id: {}", max_id+1);
         //we want to start this at ONE so we reserve the zero index for the root nodes of the forest
-        Item::new((max_id as u32) + 1, &title, &entry)
+        let mut item = Item::new((max_id as u32) + 1, "", &entry);
+        //pull any due:tomorrow style dates out of the title
+        item.set_title_from_text(&title, dates::today());
+        item
     }
 
-    #[cfg(feature="clockrust")]
-    pub fn add_char_to_report_dialog(&mut self, c: char){
-        let mut rfp = self.report_file_path();
-        // debug!("Fired add_char_to_report_dialog, char = {}", c);
-        //we insert at the cursor position
-        let idx = rfp.len() as u16 - self.cursor_offset;
-        rfp.insert(idx as usize, c);
-        self.set_report_file_path(&rfp);
-        // self.file_path.push(c);
-        self.cursor_position[0] += 1;
+    ///Add character to current input at the cursor
+    pub fn add_character(&mut self, c: char) {
+        self.current_item.insert(self.input_cursor, c);
+        self.input_cursor += c.len_utf8();
     }
 
-    #[cfg(feature="clockrust")]
-    pub fn remove_char_from_report_dialog(&mut self){
-        let mut rfp = self.report_file_path();
-        if rfp.is_empty(){
-           return;
+    ///Remove the character before the cursor when inputting an item - all of it, accents and all
+    pub fn remove_character(&mut self) {
+        let start = prev_grapheme(&self.current_item, self.input_cursor);
+        self.current_item.drain(start..self.input_cursor);
+        self.input_cursor = start;
+    }
+
+    ///Remove the character under the cursor when inputting an item
+    pub fn delete_character(&mut self) {
+        let end = next_grapheme(&self.current_item, self.input_cursor);
+        self.current_item.drain(self.input_cursor..end);
+    }
+
+    ///The lines of the item input as shown in the box - byte ranges into current_item.
+    /// Lines wider than the box wrap after the last space that fits, or mid-word if there isn't one
+    pub fn input_lines(&self) -> Vec<(usize, usize)> {
+        let width = if self.input_width == 0 { usize::MAX } else { self.input_width };
+        let mut lines = Vec::new();
+        let mut line_start = 0;
+        for line in self.current_item.split('\n') {
+            let mut start = line_start;
+            let mut used = 0;
+            let mut after_space = None;
+            for (i, g) in line.grapheme_indices(true) {
+                let at = line_start + i;
+                //always at least one character a line, however wide it is
+                if used + g.width() > width && at > start {
+                    let wrap_at = after_space.unwrap_or(at);
+                    lines.push((start, wrap_at));
+                    used = self.current_item[wrap_at..at].width();
+                    start = wrap_at;
+                    after_space = None;
+                }
+                used += g.width();
+                if g == " " {
+                    after_space = Some(at + 1);
+                }
+            }
+            lines.push((start, line_start + line.len()));
+            line_start += line.len() + 1;
         }
-        let idx_del = rfp.len() as u16 - 1  - self.cursor_offset;
+        lines
+    }
 
-        rfp.remove(idx_del as usize);
-        self.set_report_file_path(&rfp);
-        self.cursor_position[0] -= 1;
+    ///Which line of the input box the cursor is on, and how many columns along it
+    pub fn input_cursor_line_col(&self) -> (usize, usize) {
+        let lines = self.input_lines();
+        //the end of a wrapped line is the start of the next one, the cursor goes on the next
+        let line = lines.iter().rposition(|(start, _)| *start <= self.input_cursor).unwrap_or(0);
+        let col = self.current_item[lines[line].0..self.input_cursor].width();
+        (line, col)
     }
 
-    // pub fn remove_char_from_dialog(&mut self, )
-    ///Add character to current input
-    /// while keeping track of the cursor
-    pub fn add_character(&mut self, c: char) {
-        self.current_item.push(c);
-        if c == '\n' { //newline!
-            self.cursor_position[0] = 1;
-            self.cursor_position[1] += 1;
-        } else {
-            self.cursor_position[0] += 1;
-        }
+    ///Cursor left a character in the item input
+    pub fn input_left(&mut self) {
+        self.input_cursor = prev_grapheme(&self.current_item, self.input_cursor);
     }
 
-    ///Move the cursor left in the dialog
-    pub fn cursor_left(&mut self) {
-        if self.cursor_position[0] > 0 {
-            self.cursor_position[0] -= 1;
-            self.cursor_offset += 1;
-        }
+    ///Cursor right a character in the item input
+    pub fn input_right(&mut self) {
+        self.input_cursor = next_grapheme(&self.current_item, self.input_cursor);
     }
 
-    ///Move the cursor right in the dialog
-    pub fn cursor_right(&mut self, len:usize) {
-        if self.cursor_position[0] < len as u16 {
-            self.cursor_position[0] += 1;
-            self.cursor_offset -= 1;
-        }
+    ///Cursor up or down a line in the item input, staying in the same column if the line is long enough
+    pub fn input_line_up_down(&mut self, down: bool) {
+        let lines = self.input_lines();
+        let (line, col) = self.input_cursor_line_col();
+        let target = match (down, line) {
+            (true, l) if l + 1 < lines.len() => l + 1,
+            (false, l) if l > 0 => l - 1,
+            _ => return,
+        };
+        self.input_cursor = self.input_line_offset(&lines, target, col);
     }
 
-    ///Remove a character backwards when inputting  an item
-    pub fn remove_character(&mut self) {
-        //do nothing if current_item is zero length
-        if self.current_item.is_empty() {
-            return;
+    ///Cursor to the start of the line in the item input
+    pub fn input_home(&mut self) {
+        let lines = self.input_lines();
+        let (line, _) = self.input_cursor_line_col();
+        self.input_cursor = lines[line].0;
+    }
+
+    ///Cursor to the end of the line in the item input
+    pub fn input_end(&mut self) {
+        let lines = self.input_lines();
+        let (line, _) = self.input_cursor_line_col();
+        self.input_cursor = self.input_line_offset(&lines, line, usize::MAX);
+    }
+
+    ///Where column col is on the given line, or as close as we can get without going onto the next
+    fn input_line_offset(&self, lines: &[(usize, usize)], line: usize, col: usize) -> usize {
+        let (start, end) = lines[line];
+        let mut stops = Vec::new();
+        let mut stop_col = 0;
+        for (i, g) in self.current_item[start..end].grapheme_indices(true) {
+            stops.push((start + i, stop_col));
+            stop_col += g.width();
         }
-        let c = self.current_item.pop().unwrap_or('\0');
-        if c == '\n' {//deleted a new line!
-            //reduce y by 1
-            self.cursor_position[1] -= 1;
-            //we need the len of this line.....!
-            //find out length of line we are at the end of
-            let line_len = match self.current_item.rfind('\n') {
-                None =>  self.current_item.len(),
-                Some(nli) => self.current_item.len() - nli,
-            };
-            //put cursor at end of line
-            self.cursor_position[0] = line_len as u16;
-        } else {
-            //reduce x by 1
-            self.cursor_position[0] -= 1;
+        //a wrapped line's end is where the next line starts, so we can only go up to the last character
+        let wrapped = line + 1 < lines.len() && lines[line + 1].0 == end;
+        if !wrapped {
+            stops.push((end, stop_col));
+        }
+        stops.iter()
+             .rev()
+             .find(|(_, c)| *c <= col)
+             .map_or(start, |(offset, _)| *offset)
+    }
+
+    ///Cursor back to the start of this word, or the one before if we're already at the start
+    pub fn input_word_left(&mut self) {
+        let before = &self.current_item[..self.input_cursor];
+        let trimmed = before.trim_end();
+        self.input_cursor = trimmed.rfind(char::is_whitespace)
+                                   .map(|i| i + trimmed[i..].chars().next().map_or(1, char::len_utf8))
+                                   .unwrap_or(0);
+    }
+
+    ///Cursor on to the start of the next word
+    pub fn input_word_right(&mut self) {
+        let after = &self.current_item[self.input_cursor..];
+        let word_end = after.find(char::is_whitespace).unwrap_or(after.len());
+        let next_word = after[word_end..].find(|c: char| !c.is_whitespace()).unwrap_or(after.len() - word_end);
+        self.input_cursor += word_end + next_word;
+    }
+
+    ///The text being edited in the save (or report) dialog
+    pub fn dialog_text(&self) -> String {
+        match self.input_mode {
+            #[cfg(feature="clockrust")]
+            InputMode::PrintReport => self.report_file_path(),
+            _ => self.file_path(),
         }
     }
 
-    ///In save dialog, add character
-    pub fn add_save_input_char(&mut self, c: char) {
-        //we insert at the cursor position
-        let insert_index = self.file_path().len() as u16 - self.cursor_offset;
-        let mut fp = self.file_path();
-        fp.insert(insert_index as usize, c);
-        self.set_file_path(&fp);
-        self.cursor_position[0] += 1;
+    fn set_dialog_text(&mut self, text: &str) {
+        match self.input_mode {
+            #[cfg(feature="clockrust")]
+            InputMode::PrintReport => self.set_report_file_path(text),
+            _ => self.set_file_path(text),
+        }
+    }
+
+    ///How many columns in the dialog's cursor is
+    pub fn dialog_cursor_column(&self) -> u16 {
+        let text = self.dialog_text();
+        text[..self.dialog_cursor.min(text.len())].width() as u16
+    }
+
+    ///Move the cursor left in the save and report dialogs
+    pub fn cursor_left(&mut self) {
+        self.dialog_cursor = prev_grapheme(&self.dialog_text(), self.dialog_cursor);
     }
 
-    ///Remove characters backward in save dialog
-    pub fn remove_save_file_char(&mut self) {
-        if self.file_path().is_empty() {
-            debug!("Nothing to remove, file_path is empty");
+    ///Move the cursor right in the save and report dialogs
+    pub fn cursor_right(&mut self) {
+        self.dialog_cursor = next_grapheme(&self.dialog_text(), self.dialog_cursor);
+    }
+
+    ///In the save and report dialogs, add character at the cursor
+    pub fn add_dialog_char(&mut self, c: char) {
+        let mut text = self.dialog_text();
+        text.insert(self.dialog_cursor, c);
+        self.set_dialog_text(&text);
+        self.dialog_cursor += c.len_utf8();
+    }
+
+    ///Remove characters backward in the save and report dialogs
+    pub fn remove_dialog_char(&mut self) {
+        let mut text = self.dialog_text();
+        let start = prev_grapheme(&text, self.dialog_cursor);
+        text.drain(start..self.dialog_cursor);
+        self.set_dialog_text(&text);
+        self.dialog_cursor = start;
+    }
+
+    ///Show only the selected item and what's under it, as if it were the whole list
+    pub fn hoist_selected(&mut self) {
+        let (id, title) = match self.selected_item() {
+            Some(item) => (item.id, item.title.clone()),
+            None => return,
+        };
+        //an empty view would leave nothing to select
+        if self.item_tree.get(&id).map_or(0, Vec::len) == 0 {
+            self.set_status_message(&format!("{} has nothing under it to hoist", title));
             return;
         }
-        // debug!("remove_save_file_char, where x={}, y={} ", self.cursor_position[0], self.cursor_position[1]);
+        debug!("Hoisting item {}", id);
+        self.hoisted_id = id;
+        self.rebuild_list();
+        let selection = if self.items.items.is_empty() { None } else { Some(0) };
+        self.items.state.select(selection);
+    }
 
-        //-1 because we want to delete BEHIND the cursor
-        let delete_pos = self.file_path().len() - 1 - self.cursor_offset as usize;
-        let mut fp = self.file_path();
-        fp.remove(delete_pos);
-        self.set_file_path(&fp);
-        self.cursor_position[0] -= 1;
+    ///Go back out one level from the hoisted item, to its parent - and out of hoisting altogether from the top
+    pub fn unhoist(&mut self) {
+        if self.hoisted_id == 0 {
+            return;
+        }
+        let previous = self.hoisted_id;
+        self.hoisted_id = self.parent_id_of(previous).unwrap_or(0);
+        debug!("Unhoisted from {} to {}", previous, self.hoisted_id);
+        self.select_item(previous);
+    }
+
+    ///Titles of the hoisted item and all its ancestors, from the root down
+    pub fn hoisted_path(&self) -> Vec<String> {
+        self.path_to(self.hoisted_id)
+    }
+
+    ///Titles of the selected item and all its ancestors, from the root down
+    pub fn selected_path(&self) -> Vec<String> {
+        self.selected_item()
+            .map(|item| self.path_to(item.id))
+            .unwrap_or_default()
+    }
+
+    ///Titles of the item with this id and all its ancestors, from the root down
+    fn path_to(&self, id: u32) -> Vec<String> {
+        let mut path = Vec::new();
+        let mut id = id;
+        while id != 0 {
+            let item = self.parent_id_of(id)
+                           .and_then(|parent_id| self.item_tree.get(&parent_id))
+                           .and_then(|bucket| bucket.iter().find(|i| i.id == id));
+            match item {
+                Some(item) => {
+                    path.insert(0, item.title.clone());
+                    id = item.parent_id;
+                }
+                None => break,
+            }
+        }
+        path
+    }
+
+    ///The list name, followed by the path down to the hoisted item if we are hoisted
+    pub fn breadcrumb_title(&mut self) -> String {
+        let mut title = self.list_name();
+        self.hoisted_path()
+            .iter()
+            .for_each(|t| title.push_str(&format!(" > {}", t)));
+        title
     }
 
     ///Returns the name (which is the filename) of this list
@@ -1301,6 +2725,28 @@ impl RutuduList {
         self.unsaved = false;
     }
 
+    ///A brand new daily list gets the recurring items that are due from the latest daily list before it
+    pub fn seed_recurring_items(&mut self, dir_path: &str) {
+        let own_path = self.file_path();
+        let own_name = own_path.rsplit('/').next().unwrap_or(&own_path).to_string();
+        let mut daily_lists = match self.scan_directory(dir_path, "rtd") {
+            Ok(lists) => lists,
+            Err(why) => {
+                error!("Unable to look for older lists to seed from: {}", why);
+                return;
+            }
+        };
+        let daily_rx = Regex::new(r"rutudu\d{8}\.rtd$").unwrap();
+        daily_lists.retain(|l| daily_rx.is_match(l) && !l.ends_with(&own_name));
+        daily_lists.sort();
+        if let Some(latest) = daily_lists.last() {
+            debug!("Seeding recurring items from {}", latest);
+            if let Err(why) = db::import_recurring_items(self, latest, dates::today()) {
+                error!("Failed to seed recurring items from {}: {}", latest, why);
+            }
+        }
+    }
+
     ///Will scan the current directory once, to prevent loop jamming
     pub fn scan_files_once(&mut self) {
         if self.has_scanned {
@@ -1369,14 +2815,13 @@ impl RutuduList {
 
     }
 
-    // #[cfg(feature = "clockrust")]
-    pub fn highlight_color(&self) -> Color{
+    ///The selected item stands out, but keeps looking tracked or marked
+    pub fn highlight_style(&self) -> Style{
         match self.selected_item(){
-            None => Color::Cyan,
-            // Some(item) => if item.tracking_time { Color::Red } else { Color::Cyan }
-            Some(item) => if item.tracking_time { Color::Red }
-                                else if item.color != Color::White { item.color }
-                                else { Color::Cyan }
+            None => self.theme.highlight,
+            Some(item) => if item.tracking_time { self.theme.highlight.patch(self.theme.tracking) }
+                                else if let Some(mark) = item.mark { self.theme.highlight.patch(self.theme.mark_style(mark)) }
+                                else { self.theme.highlight }
         }
     }
 
@@ -1413,24 +2858,280 @@ impl RutuduList {
 
     pub fn export_as_markup(&mut self) -> Result<(), Box<dyn Error>>{
         let name = self.list_name().clone();
-        export::write_list_as_markdown(&name, &self.item_tree, &format!("{}.md", name))
+        export::write_list_as_markdown(&name, &self.item_tree, &format!("{}.md", name))?;
+        self.set_status_message(&format!("Exported to {}.md", name));
+        Ok(())
+    }
+
+    ///Select whatever is on this row of the list, counting from the top inside the border
+    pub fn select_at_row(&mut self, row: usize, visible_rows: usize) -> Option<usize> {
+        let idx = self.item_at_row(row, visible_rows)?;
+        self.items.state.select(Some(idx));
+        Some(idx)
+    }
+
+    ///Show the detail pane on the right, then at the bottom, then not at all
+    pub fn toggle_detail_pane(&mut self) {
+        self.detail_pane = self.detail_pane.next();
+    }
+
+    ///How far the detail pane is scrolled down for the selected item
+    pub fn detail_scroll(&self) -> u16 {
+        match (self.selected_item(), self.detail_scroll) {
+            (Some(item), (id, scroll)) if item.id == id => scroll,
+            _ => 0,
+        }
+    }
+
+    ///Scroll the detail pane a line, without moving around the list or past the last line
+    pub fn scroll_detail(&mut self, down: bool) {
+        let id = match self.selected_item() {
+            Some(item) => item.id,
+            None => return,
+        };
+        let scroll = self.detail_scroll();
+        let last_line = self.detail_lines().len().saturating_sub(1).min(u16::MAX as usize) as u16;
+        let scroll = if down { scroll.saturating_add(1).min(last_line) } else { scroll.saturating_sub(1) };
+        self.detail_scroll = (id, scroll);
+    }
+
+    ///Everything about the selected item - what it is, where it lives, when it's due, how far along it is,
+    /// then the entry in full
+    pub fn detail_lines(&self) -> Vec<Spans<'static>> {
+        let item = match self.selected_item() {
+            Some(item) => item,
+            None => return vec![Spans::from(Span::styled("Nothing selected", self.theme.subtle))],
+        };
+        let theme = &self.theme;
+        let field = |label: &str, value: String, style: Style| Spans::from(vec![
+            Span::styled(format!("{:<10}", label), theme.subtle),
+            Span::styled(value, style),
+        ]);
+        let mut lines = vec![
+            Spans::from(Span::styled(item.title.clone(), theme.status_style(&item.complete).add_modifier(Modifier::BOLD))),
+            Spans::from(""),
+            field("id", item.id.to_string(), theme.text),
+        ];
+        let parent = match self.path_to(item.parent_id).pop() {
+            Some(title) => format!("{} ({})", title, item.parent_id),
+            None => "none, it's at the top".to_string(),
+        };
+        lines.push(field("parent", parent, theme.text));
+        lines.push(field("status", item.complete.name().to_string(), theme.status_style(&item.complete)));
+        if let Some(mark) = item.mark {
+            lines.push(field("mark", mark.name().to_string(), theme.mark_style(mark)));
+        }
+        if let Some(created) = &item.create_date {
+            lines.push(field("created", dates::format_timestamp(created), theme.text));
+        }
+        if let Some(due) = item.due_date {
+            lines.push(field("due", dates::format_date(&due), item.due_date_style(due, dates::today(), theme)));
+        }
+        if let Some(rule) = &item.recurrence {
+            lines.push(field("every", rule.to_string(), theme.text));
+        }
+        if let Some(priority) = item.priority {
+            lines.push(field("priority", format!("p{}", priority), if priority == 1 { theme.urgent } else { theme.text }));
+        }
+        if let Some(estimate) = item.estimate {
+            lines.push(field("estimate", dates::format_estimate(estimate), theme.text));
+        }
+        if item.descendants_total > 0 {
+            let children = self.item_tree.get(&item.id).map_or(0, |c| c.len());
+            lines.push(field("children", format!("{}, {} all the way down, {} done",
+                                                 children, item.descendants_total, item.descendants_done), theme.text));
+            if item.remaining_estimate > 0 {
+                lines.push(field("left", dates::format_estimate(item.remaining_estimate), theme.text));
+            }
+        }
+        let tracking = if item.tracking_time { "clocked in" } else { "not clocked in" };
+        lines.push(field("tracking", tracking.to_string(), if item.tracking_time { theme.tracking } else { theme.text }));
+        lines.push(Spans::from(""));
+        lines.extend(item.entry_lines().into_iter().map(|line| Spans::from(line.to_string())));
+        lines
+    }
+
+    ///Which item is showing on this row of the list
+    pub fn item_at_row(&self, row: usize, visible_rows: usize) -> Option<usize> {
+        self.items.index_at_row(row, visible_rows, |i, item| self.item_height(i, item))
+    }
+
+    ///Clicking the expansion symbol opens a closed item up, or closes an open one a step
+    pub fn toggle_expansion_selected(&mut self) {
+        match self.selected_item().map(|i| i.expand.clone()) {
+            Some(ExpandStatus::Closed) => self.expand_selected(),
+            Some(_) => self.collapse_selected(),
+            None => {}
+        }
     }
 
-    ///Will mark/unmark an item
-    pub fn mark_selected_item(&mut self){
-        debug!("Marking the item...");
+    ///Start dragging the selected item
+    pub fn start_drag(&mut self) {
+        self.drag = self.selected_item().map(|i| Drag { item_id: i.id, target: None });
+    }
+
+    ///The dragged item is over the item at this index. Over its title it would go inside,
+    /// otherwise it goes before or after depending on which way we're dragging.
+    /// Nowhere to go if that's the dragged item itself or something under it
+    pub fn drag_over(&mut self, idx: Option<usize>, over_title: bool) {
+        let dragged_id = match &self.drag {
+            Some(d) => d.item_id,
+            None => return,
+        };
+        let dragged_idx = self.items.items.iter().position(|i| i.id == dragged_id);
+        let target = idx.and_then(|idx| self.items.items.get(idx).map(|i| (idx, i.id)))
+                        .filter(|(_, id)| !self.collect_subtree(dragged_id).iter().any(|i| i.id == *id))
+                        .map(|(idx, id)| {
+                            let position = if over_title {
+                                DropPosition::Inside
+                            } else if dragged_idx.is_some_and(|d| idx < d) {
+                                DropPosition::Before
+                            } else {
+                                DropPosition::After
+                            };
+                            (id, position)
+                        });
+        if let Some(drag) = self.drag.as_mut() {
+            drag.target = target;
+        }
+    }
+
+    ///Let go of the dragged item - it moves if it was over somewhere it can go
+    pub fn drop_dragged(&mut self) {
+        if let Some(Drag { item_id, target: Some((target_id, position)) }) = self.drag.take() {
+            if self.move_subtree(item_id, target_id, position) {
+                self.select_item(item_id);
+            }
+        }
+    }
+
+    ///Move an item, and everything under it, next to or into another item.
+    /// False if it can't go there - you can't put something inside itself
+    pub fn move_subtree(&mut self, item_id: u32, target_id: u32, position: DropPosition) -> bool {
+        if self.collect_subtree(item_id).iter().any(|i| i.id == target_id) {
+            return false;
+        }
+        let (old_parent_id, new_parent_id) = match (self.parent_id_of(item_id), self.parent_id_of(target_id)) {
+            (Some(old), Some(target_parent)) => (old, if position == DropPosition::Inside { target_id } else { target_parent }),
+            _ => return false,
+        };
+        let mut item = match self.item_tree.get_mut(&old_parent_id)
+                                 .and_then(|b| b.iter().position(|i| i.id == item_id).map(|pos| b.remove(pos))) {
+            Some(item) => item,
+            None => return false,
+        };
+        debug!("Moving {} {:?} {}", item_id, position, target_id);
+        item.parent_id = new_parent_id;
+        match position {
+            DropPosition::Inside => {
+                self.item_tree.entry(target_id).or_default().push(item);
+                //open it up so we can see where it went
+                if let Some(target) = self.item_tree.values_mut()
+                                          .flat_map(|b| b.iter_mut())
+                                          .find(|i| i.id == target_id) {
+                    target.show_children();
+                }
+            }
+            DropPosition::Before => {
+                let bucket = self.item_tree.entry(new_parent_id).or_default();
+                let pos = bucket.iter().position(|i| i.id == target_id).unwrap_or(0);
+                bucket.insert(pos, item);
+            }
+            DropPosition::After => self.insert_after(target_id, item),
+        }
+        self.dirty_list = true;
+        self.unsaved = true;
+        true
+    }
+
+    ///Will mark/unmark an item - giving it the mark it already has takes it off
+    pub fn mark_selected_item(&mut self, mark: Option<Mark>){
+        debug!("Marking the item with {:?}...", mark);
         if let Some(idx) = self.items.state.selected(){
             let item = self.items.items[idx].clone();
-            if let Some(mut tree_item) = self.get_item_in_tree_mut(&item){
-                if tree_item.color != Color::White {
-                    tree_item.color = Color::White;
-                }else {
-                    tree_item.color = Color::LightRed;
-                }
+            if let Some(tree_item) = self.get_item_in_tree_mut(&item){
+                tree_item.mark = if tree_item.mark == mark { None } else { mark };
+                self.unsaved = true;
+            }
+        }
+        self.dirty_list = true;
+    }
 
+    ///The selected item as text to edit - the title on the first line, the entry after it
+    pub fn selected_item_as_text(&self) -> Option<String> {
+        self.selected_item()
+            .map(|item| format!("{}\n{}", item.title, item.entry_lines().join("\n")))
+    }
+
+    ///Put edited text back into the selected item, split the same way as selected_item_as_text.
+    /// Returns false if there was nothing left of the title, the item is left alone then
+    pub fn update_selected_item_from_text(&mut self, text: &str) -> bool {
+        //editors like to end files with a newline
+        let text = text.trim_end_matches(['\n', '\r']);
+        let (title, entry) = text.split_once('\n').unwrap_or((text, ""));
+        let entry = entry.replace("\r\n", "\n");
+        let item = match self.selected_item() {
+            Some(item) => item.clone(),
+            None => return false,
+        };
+        //tokens like due:tomorrow work the same as in the add dialog
+        let mut edited = item.clone();
+        let found = edited.set_title_from_text(title, dates::today());
+        if edited.title.is_empty() {
+            return false;
+        }
+        if let Some(tree_item) = self.get_item_in_tree_mut(&item){
+            if found || tree_item.title != edited.title || tree_item.entry != entry {
+                tree_item.title = edited.title;
+                tree_item.entry = entry;
+                tree_item.due_date = edited.due_date;
+                tree_item.recurrence = edited.recurrence;
+                tree_item.estimate = edited.estimate;
+                tree_item.priority = edited.priority;
+                self.unsaved = true;
             }
         }
         self.dirty_list = true;
+        true
+    }
+
+    ///Mark the selected item, or everything picked in visual mode, then back to where we were
+    pub fn pick_mark(&mut self, mark: Option<Mark>){
+        if self.has_visual_selection() {
+            self.input_mode = InputMode::Visual;
+            self.apply_to_visual_selection(false, |l| l.mark_selected_item(mark));
+        } else {
+            self.mark_selected_item(mark);
+            self.enter_edit_mode();
+        }
+    }
+
+    ///Show only items with this mark, and whatever they're under. None shows everything again
+    pub fn filter_by_mark(&mut self, mark: Option<Mark>){
+        self.input_mode = InputMode::Edit;
+        //an empty view would leave nothing to select, so keep showing what we had
+        if let Some(m) = mark {
+            if !self.subtree_has_mark(self.hoisted_id, m) {
+                self.set_status_message(&format!("No {} items", m.name()));
+                return;
+            }
+        }
+        self.mark_filter = mark;
+        match self.selected_item().map(|i| i.id) {
+            Some(id) => self.select_item(id),
+            None => self.dirty_list = true,
+        }
+        match mark {
+            Some(m) => self.set_status_message(&format!("Showing {} items", m.name())),
+            None => self.set_status_message("Showing everything"),
+        }
+    }
+
+    ///Is anything under this item marked like that?
+    fn subtree_has_mark(&self, parent_id: u32, mark: Mark) -> bool {
+        self.item_tree.get(&parent_id).is_some_and(|children| {
+            children.iter().any(|c| c.mark == Some(mark) || self.subtree_has_mark(c.id, mark))
+        })
     }
 
 }
@@ -1459,4 +3160,584 @@ mod tests{
         assert_eq!(0, list.size());
     }
 
+    #[test]
+    pub fn test_cycle_status_skips_complete(){
+        let mut list = RutuduList::default();
+        let mut item = Item::new(1, "Test item", "");
+        list.insert_item(&mut item);
+        list.cycle_selected_item_status(true);
+        assert_eq!(CompleteStatus::InProgress, list.item_tree[&0][0].complete);
+        list.cycle_selected_item_status(false);
+        list.cycle_selected_item_status(false);
+        assert_eq!(CompleteStatus::Cancelled, list.item_tree[&0][0].complete);
+        list.cycle_selected_item_status(true);
+        assert_eq!(CompleteStatus::Incomplete, list.item_tree[&0][0].complete);
+    }
+
+    #[test]
+    pub fn test_progress_rolls_up_and_auto_completes_parent(){
+        let mut list = RutuduList::default();
+        let mut parent = Item::new(1, "Parent", "");
+        list.insert_item(&mut parent);
+        for id in 2..4 {
+            let mut child = Item::new(id, "Child", "");
+            child.parent_id = 1;
+            list.insert_item(&mut child);
+        }
+        list.item_tree.get_mut(&0).unwrap()[0].expand = ExpandStatus::ShowChildren;
+        list.select_item(2);
+        list.toggle_selected_item_completion_status();
+        list.rebuild_list_if_dirty();
+        assert_eq!((1, 2), list.count_descendants(1));
+        assert_eq!("[1/2] ", list.items.items[0].progress_symbol());
+        assert_eq!(CompleteStatus::Incomplete, list.item_tree[&0][0].complete);
+
+        list.auto_complete_parents = true;
+        list.select_item(3);
+        list.toggle_selected_item_completion_status();
+        list.rebuild_list_if_dirty();
+        assert_eq!("[2/2] ", list.items.items[0].progress_symbol());
+        assert_eq!(CompleteStatus::Complete, list.item_tree[&0][0].complete);
+    }
+
+    #[test]
+    pub fn test_huge_estimates_max_out(){
+        let mut list = RutuduList::default();
+        let mut parent = Item::new(1, "Project", "");
+        list.insert_item(&mut parent);
+        for id in 2..=3 {
+            let mut child = Item::new(id, "Big job", "");
+            child.parent_id = 1;
+            child.estimate = Some(4_000_000_000);
+            list.insert_item(&mut child);
+        }
+        list.rebuild_list();
+        assert_eq!(u32::MAX, list.estimate_remaining(0));
+        assert_eq!(u32::MAX, list.items.items[0].remaining_estimate);
+    }
+
+    #[test]
+    pub fn test_yank_and_paste_subtree_gets_fresh_ids(){
+        let mut list = RutuduList::default();
+        let mut parent = Item::new(1, "Parent", "");
+        list.insert_item(&mut parent);
+        let mut child = Item::new(2, "Child", "");
+        child.parent_id = 1;
+        list.insert_item(&mut child);
+        let mut grand_child = Item::new(3, "Grandchild", "");
+        grand_child.parent_id = 2;
+        list.insert_item(&mut grand_child);
+
+        list.select_item(1);
+        list.yank_selected();
+        assert_eq!(3, list.clipboard.len());
+        list.paste(false);
+        assert_eq!(6, list.size());
+        assert_eq!(vec![1, 4], list.item_tree[&0].iter().map(|i| i.id).collect::<Vec<u32>>());
+        assert_eq!(5, list.item_tree[&4][0].id);
+        assert_eq!(6, list.item_tree[&5][0].id);
+
+        list.select_item(1);
+        list.cut_selected();
+        assert_eq!(3, list.size());
+        assert!(!list.item_tree.contains_key(&2));
+    }
+
+    #[test]
+    pub fn test_duplicate_subtree_goes_after_original(){
+        let mut list = RutuduList::default();
+        for id in 1..3 {
+            let mut item = Item::new(id, "Item", "");
+            list.insert_item(&mut item);
+        }
+        let mut child = Item::new(3, "Child", "");
+        child.parent_id = 1;
+        list.insert_item(&mut child);
+
+        list.item_tree.get_mut(&0).unwrap()[0].tracking_time = true;
+
+        list.select_item(1);
+        list.duplicate_selected();
+        assert_eq!(vec![1, 4, 2], list.item_tree[&0].iter().map(|i| i.id).collect::<Vec<u32>>());
+        assert!(!list.item_tree[&0][1].tracking_time);
+        assert_eq!(None, list.item_tree[&0][1].create_date);
+        assert_eq!("Child", list.item_tree[&4][0].title);
+        assert_eq!(4, list.item_tree[&4][0].parent_id);
+        assert!(list.clipboard.is_empty());
+    }
+
+    #[test]
+    pub fn test_send_subtree_to_another_list(){
+        let path = std::env::temp_dir().join(format!("rutudu-send-test-{}.rtd", std::process::id()));
+        let path = path.to_str().unwrap().to_string();
+        let mut target = RutuduList::default();
+        target.set_file_path(&path);
+        for id in 1..=2 {
+            let mut item = Item::new(id, "Target item", "");
+            target.insert_item(&mut item);
+        }
+        db::save_list(&target).unwrap();
+
+        let mut list = RutuduList::default();
+        list.set_file_path("source.rtd");
+        let mut parent = Item::new(1, "Project", "");
+        list.insert_item(&mut parent);
+        let mut child = Item::new(2, "Task", "");
+        child.parent_id = 1;
+        list.insert_item(&mut child);
+        let mut grand_child = Item::new(3, "Step", "");
+        grand_child.parent_id = 2;
+        list.insert_item(&mut grand_child);
+        let mut other = Item::new(4, "Other", "");
+        list.insert_item(&mut other);
+
+        list.select_item(1);
+        list.enter_send_to_list_mode();
+        list.open_file_dialog_files.items.push(path.clone());
+        list.open_file_dialog_files.state.select(Some(0));
+        list.choose_send_target_from_file_dialog();
+        assert_eq!(vec![0, 1, 2], list.send_parent_choices.items.iter().map(|(id, _)| *id).collect::<Vec<u32>>());
+        list.send_parent_down();
+        list.send_parent_down();
+        list.send_selected_to_target();
+
+        let mut reloaded = RutuduList::default();
+        db::load_list(&mut reloaded, &path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(5, reloaded.size());
+        assert_eq!(vec![1, 2], reloaded.item_tree[&0].iter().map(|i| i.id).collect::<Vec<u32>>());
+        assert_eq!(("Project", 3), (reloaded.item_tree[&2][0].title.as_str(), reloaded.item_tree[&2][0].id));
+        assert_eq!(("Task", 3), (reloaded.item_tree[&3][0].title.as_str(), reloaded.item_tree[&3][0].parent_id));
+        assert_eq!(("Step", 4), (reloaded.item_tree[&4][0].title.as_str(), reloaded.item_tree[&4][0].parent_id));
+        assert_eq!(5, reloaded.item_tree[&4][0].id);
+
+        assert_eq!(1, list.size());
+        assert_eq!(vec![4], list.item_tree[&0].iter().map(|i| i.id).collect::<Vec<u32>>());
+        assert!(!list.item_tree.contains_key(&1) || list.item_tree[&1].is_empty());
+        assert!(!list.item_tree.contains_key(&2) || list.item_tree[&2].is_empty());
+    }
+
+    #[test]
+    pub fn test_sort_recursively_by_title(){
+        let mut list = RutuduList::default();
+        for (id, title) in [(1, "banana"), (2, "Apple"), (3, "cherry")] {
+            let mut item = Item::new(id, title, "");
+            list.insert_item(&mut item);
+        }
+        for (id, title) in [(4, "zebra"), (5, "ant")] {
+            let mut item = Item::new(id, title, "");
+            item.parent_id = 3;
+            list.insert_item(&mut item);
+        }
+        list.items.state.select(None);
+        list.sort_selected(SortKey::Title, true);
+        assert_eq!(vec![2, 1, 3], list.item_tree[&0].iter().map(|i| i.id).collect::<Vec<u32>>());
+        assert_eq!(vec![5, 4], list.item_tree[&3].iter().map(|i| i.id).collect::<Vec<u32>>());
+    }
+
+    #[test]
+    pub fn test_expand_to_depth_and_collapse_all(){
+        let mut list = RutuduList::default();
+        let mut root = Item::new(1, "Root", "");
+        list.insert_item(&mut root);
+        let mut child = Item::new(2, "Child", "");
+        child.parent_id = 1;
+        list.insert_item(&mut child);
+        let mut grand_child = Item::new(3, "Grandchild", "");
+        grand_child.parent_id = 2;
+        list.insert_item(&mut grand_child);
+
+        list.expand_to_depth(2);
+        assert_eq!(vec![1, 2], list.items.items.iter().map(|i| i.id).collect::<Vec<u32>>());
+        list.expand_all(ExpandStatus::ShowChildren);
+        assert_eq!(3, list.items.items.len());
+        //grandchild has no children, so it stays closed
+        assert_eq!(ExpandStatus::Closed, list.item_tree[&2][0].expand);
+
+        list.select_item(3);
+        list.collapse_all();
+        assert_eq!(1, list.items.items.len());
+        assert_eq!(Some(0), list.items.state.selected());
+    }
+
+    #[test]
+    pub fn test_hoist_and_unhoist(){
+        let mut list = RutuduList::default();
+        list.set_file_path("hoist.rtd");
+        let mut root = Item::new(1, "Project", "");
+        list.insert_item(&mut root);
+        let mut child = Item::new(2, "Phase", "");
+        child.parent_id = 1;
+        list.insert_item(&mut child);
+        let mut grand_child = Item::new(3, "Task", "");
+        grand_child.parent_id = 2;
+        list.insert_item(&mut grand_child);
+
+        list.select_item(1);
+        list.hoist_selected();
+        list.hoist_selected();
+        assert_eq!(vec![3], list.items.items.iter().map(|i| i.id).collect::<Vec<u32>>());
+        assert_eq!("hoist.rtd* > Project > Phase", list.breadcrumb_title());
+
+        //nothing under Task, so it stays as it was
+        list.hoist_selected();
+        assert_eq!(2, list.hoisted_id);
+        assert_eq!(Some("Task has nothing under it to hoist"), list.status_message());
+
+        list.unhoist();
+        assert_eq!(1, list.hoisted_id);
+        assert_eq!(2, list.selected_item().unwrap().id);
+        list.unhoist();
+        assert_eq!(0, list.hoisted_id);
+    }
+
+    #[test]
+    pub fn test_mark_and_filter_by_mark(){
+        let mut list = RutuduList::default();
+        list.set_file_path("marks.rtd");
+        let mut root = Item::new(1, "Project", "");
+        list.insert_item(&mut root);
+        let mut child = Item::new(2, "Task", "");
+        child.parent_id = 1;
+        list.insert_item(&mut child);
+        let mut other = Item::new(3, "Other", "");
+        list.insert_item(&mut other);
+
+        list.expand_all(ExpandStatus::ShowChildren);
+        list.select_item(2);
+        list.pick_mark(Some(Mark::Review));
+        list.rebuild_list_if_dirty();
+        assert_eq!(Some(Mark::Review), list.selected_item().unwrap().mark);
+
+        //the parent stays so we can see where it lives, even collapsed
+        list.collapse_all();
+        list.filter_by_mark(Some(Mark::Review));
+        assert_eq!(vec![1, 2], list.items.items.iter().map(|i| i.id).collect::<Vec<u32>>());
+        list.filter_by_mark(None);
+        assert_eq!(vec![1, 3], list.items.items.iter().map(|i| i.id).collect::<Vec<u32>>());
+
+        //nothing to show, so nothing changes
+        list.filter_by_mark(Some(Mark::Idea));
+        assert_eq!(None, list.mark_filter);
+        assert_eq!(Some("No idea items"), list.status_message());
+        list.rebuild_list_if_dirty();
+        assert_eq!(vec![1, 3], list.items.items.iter().map(|i| i.id).collect::<Vec<u32>>());
+
+        //same mark again takes it off
+        list.expand_all(ExpandStatus::ShowChildren);
+        list.select_item(2);
+        list.pick_mark(Some(Mark::Review));
+        list.rebuild_list_if_dirty();
+        assert_eq!(None, list.selected_item().unwrap().mark);
+    }
+
+    #[test]
+    pub fn test_row_to_index_follows_scrolling(){
+        let mut list = RutuduList::default();
+        for id in 1..=6 {
+            let mut item = Item::new(id, "Item", "");
+            list.insert_item(&mut item);
+        }
+        list.rebuild_list();
+        //the second item is open, so it takes two lines
+        list.items.items[1].expand = ExpandStatus::Open;
+        list.items.state.select(Some(0));
+        assert_eq!(Some(1), list.item_at_row(2, 4));
+        assert_eq!(Some(2), list.item_at_row(3, 4));
+        assert_eq!(None, list.item_at_row(4, 4));
+
+        //selecting the last one scrolls the list so it's at the bottom
+        list.items.state.select(Some(5));
+        assert_eq!(Some(2), list.item_at_row(0, 4));
+        assert_eq!(Some(5), list.select_at_row(3, 4));
+    }
+
+    #[test]
+    pub fn test_long_items_wrap(){
+        let mut list = RutuduList::default();
+        let mut item = Item::new(1, "a title long enough to wrap", "");
+        list.insert_item(&mut item);
+        let mut item = Item::new(2, "short", "");
+        list.insert_item(&mut item);
+        list.rebuild_list();
+        list.list_width = 24;
+
+        //wrapped lines line up under the title, past the "0.0: [+] "
+        let rows: Vec<String> = list.item_lines(0, &list.items.items[0])
+            .iter()
+            .map(|spans| spans.0.iter().map(|s| s.content.as_ref()).collect())
+            .collect();
+        assert_eq!(vec!["0.0: [+] a title long ", "         enough to wrap"], rows);
+        assert_eq!(1, list.item_height(1, &list.items.items[1]));
+
+        //the second row of the first item is still the first item
+        list.items.state.select(Some(0));
+        assert_eq!(Some(0), list.item_at_row(1, 3));
+        assert_eq!(Some(1), list.select_at_row(2, 3));
+        //only room for the selection, so that's all that shows
+        assert_eq!(Some(1), list.item_at_row(0, 1));
+    }
+
+    #[test]
+    pub fn test_tree_guides(){
+        let mut list = RutuduList::default();
+        for (id, parent_id) in [(1, 0), (2, 1), (3, 1), (4, 2), (5, 0)] {
+            let mut item = Item::new(id, "Item", "");
+            item.parent_id = parent_id;
+            list.insert_item(&mut item);
+        }
+        list.expand_all(ExpandStatus::ShowChildren);
+        list.rebuild_list_if_dirty();
+        let guides: Vec<&str> = list.items.items.iter().map(|i| i.guide.as_str()).collect();
+        assert_eq!(vec!["", "├─ ", "│  └─ ", "└─ ", ""], guides);
+
+        let four = list.items.items[2].clone();
+        assert_eq!(4, four.id);
+        assert_eq!("│  └─ 2.2: ", four.number_prefix(list.item_number(2)));
+        list.toggle_numbers();
+        assert_eq!("│  └─ ", four.number_prefix(list.item_number(2)));
+        //the lines under an item carry on the ones above it
+        assert_eq!("│     ", four.guide_below());
+    }
+
+    #[test]
+    pub fn test_detail_pane_shows_selected_item(){
+        let mut list = RutuduList::default();
+        let mut parent = Item::new(1, "Project", "");
+        list.insert_item(&mut parent);
+        let mut child = Item::new(2, "Task", "line one\nline two");
+        child.parent_id = 1;
+        child.mark = Some(Mark::Idea);
+        child.complete = CompleteStatus::InProgress;
+        list.insert_item(&mut child);
+        list.expand_all(ExpandStatus::ShowChildren);
+        list.select_item(2);
+
+        let lines: Vec<String> = list.detail_lines()
+            .iter()
+            .map(|spans| spans.0.iter().map(|s| s.content.as_ref()).collect())
+            .collect();
+        assert_eq!("Task", lines[0]);
+        assert!(lines.contains(&"id        2".to_string()));
+        assert!(lines.contains(&"parent    Project (1)".to_string()));
+        assert!(lines.contains(&"status    in progress".to_string()));
+        assert!(lines.contains(&"mark      idea".to_string()));
+        assert_eq!(vec!["line one", "line two"], lines[lines.len() - 2..].to_vec());
+
+        //scrolling is per item, and leaves the list alone
+        list.scroll_detail(true);
+        list.scroll_detail(true);
+        assert_eq!(2, list.detail_scroll());
+        assert_eq!(2, list.selected_item().unwrap().id);
+        //but not past the last line
+        (0..lines.len() + 5).for_each(|_| list.scroll_detail(true));
+        assert_eq!(lines.len() - 1, list.detail_scroll() as usize);
+        list.select_item(1);
+        assert_eq!(0, list.detail_scroll());
+        list.scroll_detail(false);
+        assert_eq!(0, list.detail_scroll());
+    }
+
+    #[test]
+    pub fn test_drag_and_drop_reorders_and_reparents(){
+        let mut list = RutuduList::default();
+        for id in 1..=3 {
+            let mut item = Item::new(id, "Item", "");
+            list.insert_item(&mut item);
+        }
+        let mut child = Item::new(4, "Child", "");
+        child.parent_id = 1;
+        list.insert_item(&mut child);
+        list.rebuild_list();
+        let ids = |l: &RutuduList| l.items.items.iter().map(|i| i.id).collect::<Vec<u32>>();
+
+        //drag the last one up over the first, then drop it inside the second
+        list.select_item(3);
+        list.start_drag();
+        list.drag_over(Some(0), false);
+        assert_eq!(Some((1, DropPosition::Before)), list.drag.as_ref().unwrap().target);
+        list.drop_dragged();
+        assert_eq!(vec![3, 1, 2], ids(&list));
+
+        list.select_item(1);
+        list.start_drag();
+        list.drag_over(Some(2), true);
+        list.drop_dragged();
+        assert_eq!(vec![3, 2, 1], ids(&list));
+        assert_eq!(Some(2), list.parent_id_of(1));
+        assert_eq!(Some(1), list.parent_id_of(4));
+
+        //nowhere to go inside itself
+        assert!(!list.move_subtree(2, 4, DropPosition::Inside));
+    }
+
+    #[test]
+    pub fn test_visual_selection_completes_all(){
+        let mut list = RutuduList::default();
+        for id in 1..5 {
+            let mut item = Item::new(id, "Item", "");
+            list.insert_item(&mut item);
+        }
+        list.select_item(1);
+        list.enter_visual_mode(true);
+        list.down();
+        list.down();
+        list.toggle_pick_selected();
+        assert_eq!(vec![1, 2, 3], list.visual_selection_ids());
+        list.down();
+        list.toggle_pick_selected();
+        assert_eq!(vec![1, 2, 3, 4], list.visual_selection_ids());
+        list.apply_to_visual_selection(false, |l| l.toggle_selected_item_completion_status());
+        assert!(list.item_tree[&0].iter().all(|i| i.complete == CompleteStatus::Complete));
+        assert!(list.input_mode == InputMode::Edit);
+    }
+
+    #[test]
+    pub fn test_completing_recurring_item_spawns_next(){
+        let mut list = RutuduList::default();
+        let mut item = Item::new(1, "Standup", "");
+        item.recurrence = Some(Recurrence::Daily);
+        list.insert_item(&mut item);
+        list.toggle_selected_item_completion_status();
+        assert_eq!(2, list.size());
+        let next = &list.item_tree[&0][1];
+        assert_eq!(2, next.id);
+        assert_eq!(CompleteStatus::Incomplete, next.complete);
+        assert_eq!(Some(dates::today() + chrono::Duration::days(1)), next.due_date);
+
+        //undone and done again, still just the one next occurrence
+        list.rebuild_list_if_dirty();
+        list.select_item(1);
+        list.toggle_selected_item_completion_status();
+        list.toggle_selected_item_completion_status();
+        assert_eq!(2, list.size());
+        assert_eq!(None, list.item_tree[&0][0].recurrence);
+        assert_eq!(Some(Recurrence::Daily), list.item_tree[&0][1].recurrence);
+
+        //nothing comes after the end of the calendar
+        let mut last = Item::new(3, "Last one", "");
+        last.recurrence = Some(Recurrence::Weekly);
+        last.due_date = Some(NaiveDate::MAX);
+        list.insert_item(&mut last);
+        list.rebuild_list_if_dirty();
+        list.select_item(3);
+        list.toggle_selected_item_completion_status();
+        assert_eq!(3, list.size());
+        assert_eq!(CompleteStatus::Complete, list.item_tree[&0][2].complete);
+    }
+
+    #[test]
+    pub fn test_multi_line_input_cursor(){
+        let mut list = RutuduList::default();
+        list.enter_insert_mode(InputMode::InsertAtRoot);
+        "Title\nsome words here".chars().for_each(|c| list.add_character(c));
+        //wraps after the last space that fits
+        list.input_width = 8;
+        assert_eq!(vec![(0, 5), (6, 11), (11, 17), (17, 21)], list.input_lines());
+        assert_eq!((3, 4), list.input_cursor_line_col());
+
+        list.input_line_up_down(false);
+        assert_eq!((2, 4), list.input_cursor_line_col());
+        list.input_line_up_down(false);
+        list.input_line_up_down(false);
+        assert_eq!(4, list.input_cursor);
+        list.input_home();
+        list.input_word_right();
+        assert_eq!(6, list.input_cursor);
+        list.input_word_right();
+        assert_eq!(11, list.input_cursor);
+        list.input_word_left();
+        assert_eq!(6, list.input_cursor);
+        list.input_line_up_down(false);
+        list.input_end();
+        list.add_character('!');
+        list.input_home();
+        list.delete_character();
+        assert_eq!("itle!\nsome words here", list.current_item);
+
+        let item = list.get_current_input_as_item();
+        assert_eq!("itle!", item.title);
+        assert_eq!("some words here", item.entry);
+        assert_eq!(0, list.input_cursor);
+    }
+
+    #[test]
+    pub fn test_unicode_input_editing(){
+        let mut list = RutuduList::default();
+        list.enter_insert_mode(InputMode::InsertAtRoot);
+        //a thumbs up with a skin tone is two chars but one character on screen, two columns wide
+        "日本 👍🏽e\u{301}".chars().for_each(|c| list.add_character(c));
+        assert_eq!((0, 8), list.input_cursor_line_col());
+        list.input_left();
+        assert_eq!((0, 7), list.input_cursor_line_col());
+        list.input_left();
+        list.remove_character();
+        assert_eq!("日本👍🏽e\u{301}", list.current_item);
+        assert_eq!((0, 4), list.input_cursor_line_col());
+        list.delete_character();
+        assert_eq!("日本e\u{301}", list.current_item);
+
+        //wide characters wrap by how much room they take up
+        list.input_width = 3;
+        assert_eq!(vec![(0, 3), (3, 9)], list.input_lines());
+        assert_eq!((1, 2), list.input_cursor_line_col());
+        list.input_line_up_down(false);
+        assert_eq!(0, list.input_cursor);
+        list.input_line_up_down(true);
+        assert_eq!(3, list.input_cursor);
+        list.input_end();
+        assert_eq!((1, 3), list.input_cursor_line_col());
+
+        list.set_file_path("日本.rtd");
+        list.enter_save_mode();
+        assert_eq!(8, list.dialog_cursor_column());
+        list.cursor_left();
+        list.cursor_left();
+        list.cursor_left();
+        list.cursor_left();
+        list.cursor_left();
+        assert_eq!(2, list.dialog_cursor_column());
+        list.remove_dialog_char();
+        list.add_dialog_char('ü');
+        assert_eq!("ü本.rtd", list.file_path());
+        assert_eq!(1, list.dialog_cursor_column());
+    }
+
+    #[test]
+    pub fn test_edit_item_as_text(){
+        let mut list = RutuduList::default();
+        let mut item = Item::new(1, "Title", "\nold entry");
+        list.insert_item(&mut item);
+        list.select_item(1);
+        assert_eq!(Some("Title\nold entry".to_string()), list.selected_item_as_text());
+
+        assert!(list.update_selected_item_from_text("New title\nline one\nline two\n"));
+        list.rebuild_list_if_dirty();
+        let item = list.selected_item().unwrap();
+        assert_eq!("New title", item.title);
+        assert_eq!("line one\nline two", item.entry);
+        assert!(list.unsaved);
+
+        //no title, no change
+        assert!(!list.update_selected_item_from_text("\n\nsomething"));
+        assert_eq!("New title", list.selected_item().unwrap().title);
+
+        //same tokens as the add dialog
+        assert!(list.update_selected_item_from_text("Retitled due:2026-11-01 every:weekly est:2h pri:1\nline one"));
+        list.rebuild_list_if_dirty();
+        let item = list.selected_item().unwrap();
+        assert_eq!("Retitled", item.title);
+        assert_eq!(NaiveDate::from_ymd_opt(2026, 11, 1), item.due_date);
+        assert_eq!(Some("weekly".to_string()), item.recurrence.as_ref().map(|r| r.to_string()));
+        assert_eq!(Some(120), item.estimate);
+        assert_eq!(Some(1), item.priority);
+
+        //and leave what they don't mention alone
+        assert!(list.update_selected_item_from_text("Retitled again pri:2"));
+        list.rebuild_list_if_dirty();
+        let item = list.selected_item().unwrap();
+        assert_eq!(("Retitled again", Some(2), Some(120)), (item.title.as_str(), item.priority, item.estimate));
+        assert!(!list.update_selected_item_from_text("due:tomorrow"));
+    }
+
 }
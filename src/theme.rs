@@ -0,0 +1,335 @@
+use std::str::FromStr;
+
+use tui::style::{Color, Modifier, Style};
+
+use crate::model::{CompleteStatus, Mark};
+
+///Every colour (and bold, underline...) rutudu draws with, so they can all be changed in one spot
+#[derive(Clone, Debug, PartialEq)]
+pub struct Theme {
+    pub name: String,
+    ///Items that aren't started yet
+    pub text: Style,
+    ///Complete and cancelled items
+    pub done: Style,
+    pub in_progress: Style,
+    pub blocked: Style,
+    pub waiting: Style,
+    pub mark_urgent: Style,
+    pub mark_waiting: Style,
+    pub mark_review: Style,
+    pub mark_idea: Style,
+    ///The item we are clocked into
+    pub tracking: Style,
+    ///The selected item in the list
+    pub highlight: Style,
+    ///Items picked in visual mode
+    pub selection: Style,
+    ///Where a dragged item would land
+    pub drop_target: Style,
+    ///Recurrence, estimates and far off due dates
+    pub subtle: Style,
+    pub due_soon: Style,
+    pub due_today: Style,
+    pub overdue: Style,
+    ///Priority 1
+    pub urgent: Style,
+    ///The R U T U D U at the top
+    pub banner: Style,
+    pub menu_key: Style,
+    pub menu_text: Style,
+    pub menu_border: Style,
+    ///Borders and text of popups
+    pub dialog: Style,
+    ///What's being typed in a popup
+    pub dialog_input: Style,
+    ///Things to pick from in a popup
+    pub dialog_item: Style,
+    ///The one picked
+    pub dialog_selected: Style,
+    pub status_mode: Style,
+    pub status_text: Style,
+    pub unsaved: Style,
+    pub message: Style,
+    pub separator: Style,
+    pub help_heading: Style,
+    pub help_keys: Style,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        let fg = |c: Color| Style::default().fg(c);
+        Theme {
+            name: "default".to_string(),
+            text: fg(Color::White),
+            done: fg(Color::DarkGray),
+            in_progress: fg(Color::LightGreen),
+            blocked: fg(Color::LightMagenta),
+            waiting: fg(Color::LightBlue),
+            mark_urgent: fg(Color::LightRed),
+            mark_waiting: fg(Color::Yellow),
+            mark_review: fg(Color::Green),
+            mark_idea: fg(Color::Blue),
+            tracking: fg(Color::Red),
+            highlight: fg(Color::Cyan).add_modifier(Modifier::BOLD),
+            selection: Style::default().bg(Color::DarkGray),
+            drop_target: fg(Color::LightYellow).add_modifier(Modifier::BOLD),
+            subtle: fg(Color::Gray),
+            due_soon: fg(Color::Yellow),
+            due_today: fg(Color::Yellow).add_modifier(Modifier::BOLD),
+            overdue: fg(Color::Red).add_modifier(Modifier::BOLD),
+            urgent: fg(Color::LightRed).add_modifier(Modifier::BOLD),
+            banner: fg(Color::LightCyan).add_modifier(Modifier::BOLD),
+            menu_key: fg(Color::Yellow),
+            menu_text: fg(Color::LightYellow),
+            menu_border: fg(Color::LightGreen),
+            dialog: fg(Color::Cyan),
+            dialog_input: fg(Color::Yellow),
+            dialog_item: fg(Color::LightCyan),
+            dialog_selected: fg(Color::LightBlue).add_modifier(Modifier::BOLD),
+            status_mode: fg(Color::Black).bg(Color::Cyan).add_modifier(Modifier::BOLD),
+            status_text: fg(Color::White),
+            unsaved: fg(Color::Yellow),
+            message: fg(Color::LightGreen),
+            separator: fg(Color::DarkGray),
+            help_heading: fg(Color::Yellow).add_modifier(Modifier::BOLD),
+            help_keys: fg(Color::LightCyan),
+        }
+    }
+}
+
+impl Theme {
+    ///For terminals with a light background - the default's whites and yellows disappear on those
+    pub fn light() -> Self {
+        let fg = |c: Color| Style::default().fg(c);
+        Theme {
+            name: "light".to_string(),
+            text: fg(Color::Black),
+            done: fg(Color::Gray),
+            in_progress: fg(Color::Green),
+            blocked: fg(Color::Magenta),
+            waiting: fg(Color::Blue),
+            mark_urgent: fg(Color::Red),
+            mark_waiting: fg(Color::Rgb(176, 112, 0)),
+            mark_review: fg(Color::Green),
+            mark_idea: fg(Color::Blue),
+            tracking: fg(Color::Red).add_modifier(Modifier::BOLD),
+            highlight: fg(Color::Blue).add_modifier(Modifier::BOLD),
+            selection: Style::default().bg(Color::Gray),
+            drop_target: fg(Color::Magenta).add_modifier(Modifier::BOLD),
+            subtle: fg(Color::DarkGray),
+            due_soon: fg(Color::Rgb(176, 112, 0)),
+            due_today: fg(Color::Rgb(176, 112, 0)).add_modifier(Modifier::BOLD),
+            overdue: fg(Color::Red).add_modifier(Modifier::BOLD),
+            urgent: fg(Color::Red).add_modifier(Modifier::BOLD),
+            banner: fg(Color::Blue).add_modifier(Modifier::BOLD),
+            menu_key: fg(Color::Magenta),
+            menu_text: fg(Color::Black),
+            menu_border: fg(Color::Green),
+            dialog: fg(Color::Blue),
+            dialog_input: fg(Color::Black),
+            dialog_item: fg(Color::Blue),
+            dialog_selected: fg(Color::Magenta).add_modifier(Modifier::BOLD),
+            status_mode: fg(Color::White).bg(Color::Blue).add_modifier(Modifier::BOLD),
+            status_text: fg(Color::Black),
+            unsaved: fg(Color::Red),
+            message: fg(Color::Green),
+            separator: fg(Color::Gray),
+            help_heading: fg(Color::Magenta).add_modifier(Modifier::BOLD),
+            help_keys: fg(Color::Blue),
+        }
+    }
+
+    ///No colour at all, just the terminal's own - everything is told apart by bold, underline etc
+    pub fn monochrome() -> Self {
+        let plain = Style::default();
+        let with = |m: Modifier| Style::default().add_modifier(m);
+        Theme {
+            name: "monochrome".to_string(),
+            text: plain,
+            done: with(Modifier::DIM),
+            in_progress: with(Modifier::BOLD),
+            blocked: with(Modifier::UNDERLINED),
+            waiting: with(Modifier::ITALIC),
+            mark_urgent: with(Modifier::UNDERLINED | Modifier::BOLD),
+            mark_waiting: with(Modifier::UNDERLINED | Modifier::ITALIC),
+            mark_review: with(Modifier::UNDERLINED),
+            mark_idea: with(Modifier::UNDERLINED | Modifier::DIM),
+            tracking: with(Modifier::SLOW_BLINK | Modifier::BOLD),
+            highlight: with(Modifier::REVERSED),
+            selection: with(Modifier::UNDERLINED),
+            drop_target: with(Modifier::REVERSED),
+            subtle: with(Modifier::DIM),
+            due_soon: plain,
+            due_today: with(Modifier::BOLD),
+            overdue: with(Modifier::BOLD | Modifier::UNDERLINED),
+            urgent: with(Modifier::BOLD),
+            banner: with(Modifier::BOLD),
+            menu_key: with(Modifier::UNDERLINED),
+            menu_text: plain,
+            menu_border: plain,
+            dialog: plain,
+            dialog_input: plain,
+            dialog_item: plain,
+            dialog_selected: with(Modifier::REVERSED),
+            status_mode: with(Modifier::REVERSED | Modifier::BOLD),
+            status_text: plain,
+            unsaved: with(Modifier::BOLD),
+            message: with(Modifier::ITALIC),
+            separator: with(Modifier::DIM),
+            help_heading: with(Modifier::BOLD | Modifier::UNDERLINED),
+            help_keys: with(Modifier::BOLD),
+        }
+    }
+
+    ///The built in themes, by name
+    pub fn named(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "default" | "dark" => Some(Theme::default()),
+            "light" => Some(Theme::light()),
+            "monochrome" | "mono" | "none" => Some(Theme::monochrome()),
+            _ => None,
+        }
+    }
+
+    ///How an item with this status looks
+    pub fn status_style(&self, status: &CompleteStatus) -> Style {
+        match status {
+            CompleteStatus::Complete | CompleteStatus::Cancelled => self.done,
+            CompleteStatus::Incomplete => self.text,
+            CompleteStatus::InProgress => self.in_progress,
+            CompleteStatus::Blocked => self.blocked,
+            CompleteStatus::Waiting => self.waiting,
+        }
+    }
+
+    ///How an item with this mark looks
+    pub fn mark_style(&self, mark: Mark) -> Style {
+        match mark {
+            Mark::Urgent => self.mark_urgent,
+            Mark::Waiting => self.mark_waiting,
+            Mark::Review => self.mark_review,
+            Mark::Idea => self.mark_idea,
+        }
+    }
+
+    ///Change one of the styles by the name it has in the config file
+    pub fn set_style(&mut self, name: &str, style: Style) -> Result<(), String> {
+        let slot = match name {
+            "text" => &mut self.text,
+            "done" => &mut self.done,
+            "in_progress" => &mut self.in_progress,
+            "blocked" => &mut self.blocked,
+            "waiting" => &mut self.waiting,
+            "mark_urgent" => &mut self.mark_urgent,
+            "mark_waiting" => &mut self.mark_waiting,
+            "mark_review" => &mut self.mark_review,
+            "mark_idea" => &mut self.mark_idea,
+            "tracking" => &mut self.tracking,
+            "highlight" => &mut self.highlight,
+            "selection" => &mut self.selection,
+            "drop_target" => &mut self.drop_target,
+            "subtle" => &mut self.subtle,
+            "due_soon" => &mut self.due_soon,
+            "due_today" => &mut self.due_today,
+            "overdue" => &mut self.overdue,
+            "urgent" => &mut self.urgent,
+            "banner" => &mut self.banner,
+            "menu_key" => &mut self.menu_key,
+            "menu_text" => &mut self.menu_text,
+            "menu_border" => &mut self.menu_border,
+            "dialog" => &mut self.dialog,
+            "dialog_input" => &mut self.dialog_input,
+            "dialog_item" => &mut self.dialog_item,
+            "dialog_selected" => &mut self.dialog_selected,
+            "status_mode" => &mut self.status_mode,
+            "status_text" => &mut self.status_text,
+            "unsaved" => &mut self.unsaved,
+            "message" => &mut self.message,
+            "separator" => &mut self.separator,
+            "help_heading" => &mut self.help_heading,
+            "help_keys" => &mut self.help_keys,
+            _ => return Err(format!("No such style in a theme: {}", name)),
+        };
+        *slot = style;
+        Ok(())
+    }
+}
+
+///A style written out in words, eg "bold yellow", "black on cyan", "italic #ff8800"
+pub fn parse_style(s: &str) -> Result<Style, String> {
+    let mut style = Style::default();
+    let mut words = s.split_whitespace();
+    while let Some(word) = words.next() {
+        let modifier = match word.to_lowercase().as_str() {
+            "bold" => Modifier::BOLD,
+            "dim" => Modifier::DIM,
+            "italic" => Modifier::ITALIC,
+            "underlined" | "underline" => Modifier::UNDERLINED,
+            "reversed" | "reverse" => Modifier::REVERSED,
+            "crossed_out" => Modifier::CROSSED_OUT,
+            "blink" => Modifier::SLOW_BLINK,
+            "on" => {
+                let bg = words.next().ok_or_else(|| format!("'on' what? in {}", s))?;
+                style = style.bg(parse_color(bg)?);
+                continue;
+            }
+            _ => {
+                style = style.fg(parse_color(word)?);
+                continue;
+            }
+        };
+        style = style.add_modifier(modifier);
+    }
+    Ok(style)
+}
+
+///Colour names as tui has them (light_red or lightred), "reset", a 0-255 index or #rrggbb
+pub fn parse_color(s: &str) -> Result<Color, String> {
+    let color = match s.to_lowercase().replace('_', "").as_str() {
+        "reset" | "default" => Color::Reset,
+        "black" => Color::Black,
+        "red" => Color::Red,
+        "green" => Color::Green,
+        "yellow" => Color::Yellow,
+        "blue" => Color::Blue,
+        "magenta" => Color::Magenta,
+        "cyan" => Color::Cyan,
+        "gray" | "grey" => Color::Gray,
+        "darkgray" | "darkgrey" => Color::DarkGray,
+        "lightred" => Color::LightRed,
+        "lightgreen" => Color::LightGreen,
+        "lightyellow" => Color::LightYellow,
+        "lightblue" => Color::LightBlue,
+        "lightmagenta" => Color::LightMagenta,
+        "lightcyan" => Color::LightCyan,
+        "white" => Color::White,
+        other => {
+            if let Some(hex) = other.strip_prefix('#') {
+                let channel = |i: usize| hex.get(i..i + 2).and_then(|h| u8::from_str_radix(h, 16).ok());
+                match (hex.len(), channel(0), channel(2), channel(4)) {
+                    (6, Some(r), Some(g), Some(b)) => Color::Rgb(r, g, b),
+                    _ => return Err(format!("Bad hex colour: {}", s)),
+                }
+            } else {
+                Color::Indexed(u8::from_str(other).map_err(|_| format!("No such colour: {}", s))?)
+            }
+        }
+    };
+    Ok(color)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn test_parse_style() {
+        assert_eq!(Ok(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)), parse_style("bold yellow"));
+        assert_eq!(Ok(Style::default().fg(Color::Black).bg(Color::LightCyan)), parse_style("black on light_cyan"));
+        assert_eq!(Ok(Style::default().fg(Color::Rgb(255, 136, 0))), parse_style("#ff8800"));
+        assert_eq!(Ok(Style::default().fg(Color::Indexed(208))), parse_style("208"));
+        assert!(parse_style("sparkly pink").is_err());
+        assert!(parse_style("red on").is_err());
+    }
+}